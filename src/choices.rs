@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use indexmap::IndexMap;
+
+use crate::config::ArgDef;
+use crate::hash::stable_hash;
+use crate::runner;
+
+/// How long a `choices_cmd` may run when a task runs or prompts.
+const RUN_TIMEOUT: Duration = Duration::from_secs(10);
+/// TAB completion has to stay snappy, so it waits much less.
+const COMPLETION_TIMEOUT: Duration = Duration::from_millis(500);
+/// Completion reuses output younger than this without running the command.
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Set the choices of every arg with a `choices_cmd` to the command's
+/// output lines. If a command fails or times out, the arg keeps no choices
+/// and any value is accepted.
+pub fn fill(args: &mut IndexMap<String, ArgDef>) {
+    let cache = cache_dir();
+    for (name, arg) in args.iter_mut() {
+        let Some(ref cmd) = arg.choices_cmd else {
+            continue;
+        };
+        match runner::output(cmd, RUN_TIMEOUT) {
+            Some(out) => {
+                if let Some(ref dir) = cache {
+                    store(&cache_file(dir, cmd), &out);
+                }
+                arg.choices = lines(&out);
+            }
+            None => eprintln!(
+                "\x1b[33mwarning: choices_cmd for '{name}' failed; any value is accepted\x1b[0m"
+            ),
+        }
+    }
+}
+
+/// Choices for TAB completion: cached output if recent, otherwise a fresh
+/// run with a short timeout, falling back to stale output.
+pub fn for_completion(cmd: &str) -> Vec<String> {
+    match cache_dir() {
+        Some(dir) => cached(&cache_file(&dir, cmd), cmd, COMPLETION_TIMEOUT),
+        None => runner::output(cmd, COMPLETION_TIMEOUT)
+            .map(|out| lines(&out))
+            .unwrap_or_default(),
+    }
+}
+
+fn cached(file: &Path, cmd: &str, timeout: Duration) -> Vec<String> {
+    let age = std::fs::metadata(file)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok());
+    let stale = std::fs::read_to_string(file).ok();
+    if let Some(ref out) = stale
+        && age.is_some_and(|age| age < CACHE_TTL)
+    {
+        return lines(out);
+    }
+    match runner::output(cmd, timeout) {
+        Some(out) => {
+            store(file, &out);
+            lines(&out)
+        }
+        None => stale.map(|out| lines(&out)).unwrap_or_default(),
+    }
+}
+
+fn lines(out: &str) -> Vec<String> {
+    out.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("muu").join("choices"))
+}
+
+/// One file per command and working directory, since commands like
+/// `git branch` list different things in different projects.
+fn cache_file(dir: &Path, cmd: &str) -> PathBuf {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let hash = stable_hash(&[cwd.as_os_str().as_encoded_bytes(), cmd.as_bytes()]);
+    dir.join(format!("{hash:016x}"))
+}
+
+/// Best effort: a cache that can't be written only costs a rerun.
+fn store(file: &Path, out: &str) {
+    if let Some(dir) = file.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let _ = std::fs::write(file, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn fill_uses_output_lines() {
+        let mut args = IndexMap::new();
+        args.insert(
+            "branch".to_string(),
+            ArgDef {
+                choices_cmd: Some("printf 'main\\n  dev \\n\\n'".to_string()),
+                ..Default::default()
+            },
+        );
+        args.insert(
+            "broken".to_string(),
+            ArgDef {
+                choices_cmd: Some("exit 1".to_string()),
+                ..Default::default()
+            },
+        );
+        fill(&mut args);
+        assert_eq!(args["branch"].choices, vec!["main", "dev"]);
+        assert!(args["broken"].choices.is_empty());
+    }
+
+    #[test]
+    fn cached_output_is_reused_until_stale() {
+        let dir = TempDir::new().unwrap();
+        let file = cache_file(dir.path(), "echo fresh");
+        let timeout = Duration::from_secs(5);
+
+        assert_eq!(cached(&file, "echo fresh", timeout), vec!["fresh"]);
+        std::fs::write(&file, "cached\n").unwrap();
+        assert_eq!(cached(&file, "echo fresh", timeout), vec!["cached"]);
+    }
+
+    #[test]
+    fn slow_command_falls_back_to_stale_cache() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("stale");
+        std::fs::write(&file, "old\n").unwrap();
+        let old = SystemTime::now() - CACHE_TTL * 2;
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let timeout = Duration::from_millis(50);
+        assert_eq!(cached(&file, "sleep 5; echo new", timeout), vec!["old"]);
+        assert!(cached(&dir.path().join("none"), "sleep 5", timeout).is_empty());
+    }
+}
@@ -1,14 +1,13 @@
+use std::path::PathBuf;
+
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::engine::SubcommandCandidates;
 
 use crate::completions::TaskCandidates;
+use crate::{docs, export, import};
 
 #[derive(Parser, Debug)]
-#[command(
-    name = "muu",
-    version,
-    about = "A minimal task runner"
-)]
+#[command(name = "muu", version, about = "A minimal task runner")]
 pub struct Cli {
     /// Show only global tasks
     #[arg(short = 'g', long = "global", global = true)]
@@ -18,6 +17,34 @@ pub struct Cli {
     #[arg(short = 'l', long = "local", global = true)]
     pub local_only: bool,
 
+    /// Filter tasks by tag (repeat for OR, comma-separate for AND)
+    #[arg(short = 't', long = "tag", global = true, value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Include deprecated tasks in the selector
+    #[arg(short = 'a', long = "all", global = true)]
+    pub all: bool,
+
+    /// Run deprecated tasks as named instead of redirecting to `replaced_by`
+    #[arg(long = "strict", global = true)]
+    pub strict: bool,
+
+    /// Run tasks even if their status checks say they are up to date
+    #[arg(short = 'f', long = "force", global = true)]
+    pub force: bool,
+
+    /// Run the task once per value combination, e.g. `--matrix env=dev,stg`
+    #[arg(long = "matrix", global = true, value_name = "NAME=VALUES")]
+    pub matrix: Vec<String>,
+
+    /// Run matrix cells in parallel
+    #[arg(short = 'p', long = "parallel", global = true)]
+    pub parallel: bool,
+
+    /// Don't prefill the selector's prompts from, or save answers to, the history
+    #[arg(long = "no-history", global = true)]
+    pub no_history: bool,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -28,18 +55,134 @@ pub enum Command {
     List,
     /// Initialize a new muu.toml
     Init,
+    /// Run every task matching --tag, in definition order
+    Run,
+    /// Open the interactive selector, even when a default task is set
+    Select,
+    /// Convert tasks from another tool into muu.toml
+    Import {
+        /// Format of the file to convert
+        format: import::Format,
+        /// File to read (defaults to the format's usual file name)
+        path: Option<PathBuf>,
+        /// Print the generated TOML instead of writing muu.toml
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Generate a Makefile, justfile, VS Code tasks or CI workflow from the tasks
+    Export {
+        /// Format to generate
+        format: export::Format,
+        /// File to write (defaults to stdout)
+        #[arg(short = 'o', long = "output")]
+        output: Option<PathBuf>,
+    },
+    /// Generate documentation for the tasks
+    Docs {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = docs::Format::Md)]
+        format: docs::Format,
+        /// File to write (defaults to stdout)
+        #[arg(short = 'o', long = "output")]
+        output: Option<PathBuf>,
+        /// Fail if the file at --output is not up to date instead of writing it
+        #[arg(long, requires = "output")]
+        check: bool,
+    },
+    /// Manage the selector's history of argument values
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+    /// Show a task's usage, arguments and command
+    Show {
+        /// Task to show
+        task: String,
+    },
+    /// Generate a standalone shell script that runs one task
+    ExportScript {
+        /// Task to export
+        task: String,
+        /// File to write (defaults to stdout)
+        #[arg(short = 'o', long = "output")]
+        output: Option<PathBuf>,
+        /// Shell that runs the task's command
+        #[arg(long, value_enum, default_value_t = export::Shell::Zsh)]
+        shell: export::Shell,
+    },
     /// Run a task (catch-all for dynamic task names)
     #[command(external_subcommand)]
     External(Vec<String>),
 }
 
+#[derive(Subcommand, Debug)]
+pub enum HistoryAction {
+    /// Forget the argument values remembered for this project
+    Clear {
+        /// Forget them for every project
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+/// Global flags that take a separate value, for finding the task name in
+/// a partial command line.
+pub const FLAGS_WITH_VALUE: &[&str] = &["-t", "--tag", "--matrix"];
+
+/// Subcommands added after tasks could already use their names. A task
+/// with one of these names is run instead, so a new subcommand doesn't
+/// make an existing task unreachable.
+pub const TASK_FIRST: &[&str] = &[
+    "run",
+    "select",
+    "show",
+    "import",
+    "export",
+    "export-script",
+    "docs",
+    "history",
+];
+
+/// Index of the subcommand or task name in `words` (which start with the
+/// program name). Global flags may come first; those that take a value
+/// skip the next word.
+pub fn command_index(words: &[String]) -> Option<usize> {
+    let mut i = 1;
+    while let Some(word) = words.get(i) {
+        if word.starts_with('-') {
+            if FLAGS_WITH_VALUE.contains(&word.as_str()) {
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+        return Some(i);
+    }
+    None
+}
+
 pub fn build_cli() -> clap::Command {
     Cli::command()
         .add(SubcommandCandidates::new(TaskCandidates))
         .mut_arg("global_only", |a| a.hide(true))
         .mut_arg("local_only", |a| a.hide(true))
+        .mut_arg("tags", |a| a.hide(true))
+        .mut_arg("all", |a| a.hide(true))
+        .mut_arg("strict", |a| a.hide(true))
+        .mut_arg("force", |a| a.hide(true))
+        .mut_arg("matrix", |a| a.hide(true))
+        .mut_arg("parallel", |a| a.hide(true))
+        .mut_arg("no_history", |a| a.hide(true))
         .mut_subcommand("init", |cmd| cmd.hide(true))
         .mut_subcommand("list", |cmd| cmd.hide(true))
+        .mut_subcommand("run", |cmd| cmd.hide(true))
+        .mut_subcommand("select", |cmd| cmd.hide(true))
+        .mut_subcommand("import", |cmd| cmd.hide(true))
+        .mut_subcommand("export", |cmd| cmd.hide(true))
+        .mut_subcommand("export-script", |cmd| cmd.hide(true))
+        .mut_subcommand("docs", |cmd| cmd.hide(true))
+        .mut_subcommand("show", |cmd| cmd.hide(true))
+        .mut_subcommand("history", |cmd| cmd.hide(true))
         .disable_help_flag(true)
         .disable_version_flag(true)
         .disable_help_subcommand(true)
@@ -0,0 +1,54 @@
+use std::path::Path;
+
+use crate::config::ResolvedTask;
+use crate::docs::{self, Format};
+use crate::error::MuuError;
+
+/// Print the docs, write them to `output`, or with `check` verify that
+/// `output` already has the current content. Markdown written to a file
+/// with `<!-- muu:docs -->` markers only replaces the marked section; any
+/// other existing file is only replaced if muu generated it.
+pub fn docs(
+    format: Format,
+    tasks: &[ResolvedTask],
+    base: &Path,
+    output: Option<&Path>,
+    check: bool,
+) -> Result<(), MuuError> {
+    let generated = docs::render(format, tasks, base);
+    let Some(path) = output else {
+        print!("{generated}");
+        return Ok(());
+    };
+
+    let existing = std::fs::read_to_string(path).ok();
+    let spliced = existing
+        .as_deref()
+        .filter(|_| format == Format::Md)
+        .and_then(|existing| docs::splice(existing, &generated));
+    let content = match (spliced, existing.as_deref()) {
+        (Some(spliced), _) => spliced,
+        (None, Some(existing)) if !docs::is_generated(existing) => {
+            return Err(MuuError::DocsTargetExists {
+                path: path.to_path_buf(),
+            });
+        }
+        (None, _) => generated,
+    };
+
+    if check {
+        if existing.as_deref() != Some(content.as_str()) {
+            return Err(MuuError::StaleDocs {
+                path: path.to_path_buf(),
+            });
+        }
+        println!("{} is up to date", path.display());
+        return Ok(());
+    }
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, content)?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use crate::config::{self, ResolvedTask};
+use crate::error::MuuError;
+use crate::export::{self, Format, Shell};
+
+/// Print the generated file, or write it to `output`.
+pub fn export(
+    format: Format,
+    tasks: &[ResolvedTask],
+    output: Option<&Path>,
+) -> Result<(), MuuError> {
+    let content = export::render(format, tasks);
+    match output {
+        Some(path) => {
+            write(path, &content)?;
+            println!("Exported {} task(s) to {}", tasks.len(), path.display());
+        }
+        None => print!("{content}"),
+    }
+    Ok(())
+}
+
+/// Print a standalone script for one task, or write it to an executable
+/// file at `output`.
+pub fn export_script(
+    name: &str,
+    tasks: &[ResolvedTask],
+    shell: Shell,
+    output: Option<&Path>,
+) -> Result<(), MuuError> {
+    let task = config::find_task(tasks, name).ok_or_else(|| MuuError::TaskNotFound {
+        name: name.to_string(),
+    })?;
+    let content = export::script(task, shell);
+    match output {
+        Some(path) => {
+            write(path, &content)?;
+            make_executable(path)?;
+            println!("Exported '{}' to {}", task.name, path.display());
+        }
+        None => print!("{content}"),
+    }
+    Ok(())
+}
+
+fn write(path: &Path, content: &str) -> Result<(), MuuError> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), MuuError> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut perms = std::fs::metadata(path)?.permissions();
+    perms.set_mode(perms.mode() | 0o111);
+    std::fs::set_permissions(path, perms)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), MuuError> {
+    Ok(())
+}
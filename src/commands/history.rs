@@ -0,0 +1,31 @@
+use crate::error::MuuError;
+use crate::history;
+
+/// Delete the current project's history file, or with `all` every one.
+pub fn clear(all: bool) -> Result<(), MuuError> {
+    let target = if all {
+        history::dir()
+    } else {
+        history::project_file()
+    };
+    let Some(path) = target else {
+        println!("No argument history to clear");
+        return Ok(());
+    };
+    let removed = if all {
+        std::fs::remove_dir_all(&path)
+    } else {
+        std::fs::remove_file(&path)
+    };
+    match removed {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    if all {
+        println!("Cleared the argument history of all projects");
+    } else {
+        println!("Cleared the argument history of this project");
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::path::Path;
+
+use crate::config;
+use crate::error::MuuError;
+use crate::import::Format;
+
+/// Convert `path` (or the format's usual file in `dir`) and write the tasks
+/// to `dir/muu.toml`, appending if it exists. Tasks whose names are already
+/// taken are skipped.
+pub fn import(
+    dir: &Path,
+    format: Format,
+    path: Option<&Path>,
+    dry_run: bool,
+) -> Result<(), MuuError> {
+    let source = match path {
+        Some(p) => dir.join(p),
+        None => {
+            let files = format.default_files();
+            files
+                .iter()
+                .map(|f| dir.join(f))
+                .find(|p| p.is_file())
+                .unwrap_or_else(|| dir.join(files[0]))
+        }
+    };
+    let content = std::fs::read_to_string(&source).map_err(|_| MuuError::ImportSourceNotFound {
+        path: source.clone(),
+    })?;
+    let mut imported = format
+        .parse(&content)
+        .map_err(|reason| MuuError::ConfigParse {
+            path: source.clone(),
+            reason,
+        })?;
+
+    let target = dir.join("muu.toml");
+    let existing = if target.is_file() {
+        Some(config::parse_config(&target)?)
+    } else {
+        None
+    };
+    if let Some(ref cfg) = existing {
+        imported.tasks.retain(|t| {
+            let taken = cfg.tasks.contains_key(&t.name);
+            if taken {
+                eprintln!(
+                    "\x1b[33mwarning: skipping '{}', already defined in muu.toml\x1b[0m",
+                    t.name
+                );
+            }
+            !taken
+        });
+    }
+    for warning in imported.warnings() {
+        eprintln!("\x1b[33mwarning: {warning}\x1b[0m");
+    }
+
+    let toml = imported.to_toml();
+    if dry_run {
+        print!("{toml}");
+        return Ok(());
+    }
+    let file_name = source.file_name().unwrap_or_default().to_string_lossy();
+    if imported.tasks.is_empty() {
+        println!("No tasks to import from {file_name}");
+        return Ok(());
+    }
+
+    let content = match existing {
+        Some(_) => {
+            let mut content = std::fs::read_to_string(&target)?;
+            if !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push('\n');
+            content.push_str(&toml);
+            content
+        }
+        None => toml,
+    };
+    std::fs::write(&target, content)?;
+    println!(
+        "Imported {} task(s) from {file_name} into muu.toml",
+        imported.tasks.len()
+    );
+    Ok(())
+}
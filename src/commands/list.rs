@@ -1,4 +1,5 @@
 use crate::config::ResolvedTask;
+use crate::namespace::{self, Row};
 
 pub fn list(tasks: &[ResolvedTask]) {
     if tasks.is_empty() {
@@ -6,20 +7,48 @@ pub fn list(tasks: &[ResolvedTask]) {
         return;
     }
 
-    let max_name = tasks.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    let rows = namespace::tree(tasks);
+
+    let max_name = rows
+        .iter()
+        .filter_map(|row| match row {
+            Row::Task { task, depth } => Some(depth * 2 + task.short_label().len()),
+            Row::Namespace { .. } => None,
+        })
+        .max()
+        .unwrap_or(0);
     let max_desc = tasks
         .iter()
         .map(|t| t.def.description.as_deref().unwrap_or("").len())
         .max()
         .unwrap_or(0);
 
-    for task in tasks {
+    for row in rows {
+        let (task, depth) = match row {
+            Row::Namespace { name, depth } => {
+                println!(
+                    "{}\x1b[1m{}:\x1b[22m",
+                    "  ".repeat(depth),
+                    namespace::leaf(name)
+                );
+                continue;
+            }
+            Row::Task { task, depth } => (task, depth),
+        };
+
         let desc = task.def.description.as_deref().unwrap_or("");
-        let source_label = format!("[{}]", task.source);
+        let mut source_label = format!("[{}]", task.source);
+        if !task.def.tags.is_empty() {
+            source_label = format!("{source_label} {}", task.tags_label());
+        }
+        if task.is_deprecated() {
+            source_label = format!("{source_label} \x1b[33m(deprecated)\x1b[39m");
+        }
+        let name = format!("{}{}", "  ".repeat(depth), task.short_label());
         if desc.is_empty() {
             println!(
                 "{:<name_w$}  {:<desc_w$} \x1b[2m{source_label}\x1b[22m",
-                task.name,
+                name,
                 "",
                 name_w = max_name,
                 desc_w = max_desc,
@@ -27,7 +56,7 @@ pub fn list(tasks: &[ResolvedTask]) {
         } else {
             println!(
                 "{:<name_w$}  \x1b[2m{desc:<desc_w$} {source_label}\x1b[22m",
-                task.name,
+                name,
                 name_w = max_name,
                 desc_w = max_desc,
             );
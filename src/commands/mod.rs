@@ -1,4 +1,9 @@
+pub mod docs;
+pub mod export;
+pub mod history;
+pub mod import;
 pub mod init;
 pub mod list;
 pub mod run;
 pub mod select;
+pub mod show;
@@ -1,18 +1,289 @@
-use crate::config::ResolvedTask;
+use std::process::Command;
+use std::thread;
+
+use indexmap::IndexMap;
+
+use crate::choices;
+use crate::config::{self, ArgDef, ResolvedTask, TaskDef};
 use crate::error::MuuError;
+use crate::matrix::{self, Cell, Matrix, Outcome};
+use crate::requires;
 use crate::runner;
-use crate::task::{expand_command, resolve_args};
+use crate::task::{self, expand_command, resolve_args, resolve_args_strict, split_words};
+
+/// Flags that change how `muu <task>` runs.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Run deprecated tasks as named instead of redirecting them.
+    pub strict: bool,
+    /// Run even if the task's status checks report it up to date.
+    pub force: bool,
+    /// `--matrix` values, added to or replacing the task's own matrix.
+    pub matrix: Vec<(String, Vec<String>)>,
+    /// Run matrix cells concurrently.
+    pub parallel: bool,
+    /// Prefill the selector's prompts from the history and save answers.
+    pub history: bool,
+}
+
+pub fn run(
+    name: &str,
+    raw_args: &[String],
+    tasks: &[ResolvedTask],
+    opts: &RunOptions,
+) -> Result<i32, MuuError> {
+    let task = redirect(lookup(tasks, name)?, tasks, opts.strict)?;
+    run_task(task, raw_args, opts)
+}
+
+/// Warn about a deprecated task and, unless `strict`, follow its
+/// `replaced_by` chain to the task to run instead.
+pub fn redirect<'a>(
+    mut task: &'a ResolvedTask,
+    tasks: &'a [ResolvedTask],
+    strict: bool,
+) -> Result<&'a ResolvedTask, MuuError> {
+    let mut visited = vec![task.name.as_str()];
+    while let Some(msg) = task.deprecation_message() {
+        eprintln!(
+            "\x1b[33mwarning: task '{}' is deprecated: {msg}\x1b[0m",
+            task.name
+        );
+        let Some(ref next) = task.def.replaced_by else {
+            break;
+        };
+        if strict || visited.contains(&next.as_str()) {
+            break;
+        }
+        task = lookup(tasks, next)?;
+        visited.push(&task.name);
+        eprintln!("\x1b[33mrunning '{}' instead\x1b[0m", task.name);
+    }
+    Ok(task)
+}
+
+fn lookup<'a>(tasks: &'a [ResolvedTask], name: &str) -> Result<&'a ResolvedTask, MuuError> {
+    config::find_task(tasks, name).ok_or_else(|| MuuError::TaskNotFound {
+        name: name.to_string(),
+    })
+}
+
+/// Run each of `selected` in order with its default args, stopping at the
+/// first failure. Deprecated ones are redirected among all `tasks`.
+pub fn run_batch(
+    selected: &[ResolvedTask],
+    tasks: &[ResolvedTask],
+    opts: &RunOptions,
+) -> Result<i32, MuuError> {
+    for task in selected {
+        eprintln!("\x1b[1m▸ {}\x1b[22m", task.name);
+        let task = redirect(task, tasks, opts.strict)?;
+        let code = run_task(task, &[], opts)?;
+        if code != 0 {
+            return Ok(code);
+        }
+    }
+    Ok(0)
+}
+
+fn run_task(task: &ResolvedTask, raw_args: &[String], opts: &RunOptions) -> Result<i32, MuuError> {
+    if !task::takes_passthrough(&task.def) && raw_args.iter().any(|a| a == "--") {
+        return Err(MuuError::PassthroughNotAccepted {
+            task: task.name.clone(),
+        });
+    }
+
+    let filled;
+    let task = if task.def.args.values().any(|a| a.choices_cmd.is_some()) {
+        let mut copy = task.clone();
+        choices::fill(&mut copy.def.args);
+        filled = copy;
+        &filled
+    } else {
+        task
+    };
+
+    let matrix = matrix::merge(&task.def.matrix, &opts.matrix);
+    if !matrix.is_empty() {
+        return run_matrix(task, raw_args, &matrix, opts);
+    }
+
+    let resolved = resolve(&task.def, &task.def.args, raw_args)?;
+    if !opts.force && is_up_to_date(task, &resolved) {
+        report_up_to_date(task);
+        return Ok(0);
+    }
+    requires::check(&task.def.requires)?;
+    execute_task(task, &resolved)
+}
+
+/// Run the task once per matrix cell, then print a summary table.
+/// Exits non-zero if any cell failed.
+fn run_matrix(
+    task: &ResolvedTask,
+    raw_args: &[String],
+    matrix: &Matrix,
+    opts: &RunOptions,
+) -> Result<i32, MuuError> {
+    if let Some(name) = matrix.keys().find(|k| !task.def.args.contains_key(*k)) {
+        return Err(MuuError::UnknownArg { name: name.clone() });
+    }
+    requires::check(&task.def.requires)?;
+
+    let cells = matrix::cells(matrix);
+    let outcomes: Vec<Outcome> = if opts.parallel {
+        thread::scope(|s| {
+            let handles: Vec<_> = cells
+                .iter()
+                .map(|cell| s.spawn(|| run_cell(task, raw_args, cell, opts)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| {
+                    h.join().unwrap_or_else(|_| Outcome::Error {
+                        message: "panicked".to_string(),
+                        code: 1,
+                    })
+                })
+                .collect()
+        })
+    } else {
+        cells
+            .iter()
+            .map(|cell| run_cell(task, raw_args, cell, opts))
+            .collect()
+    };
 
-pub fn run(name: &str, raw_args: &[String], tasks: &[ResolvedTask]) -> Result<i32, MuuError> {
-    let task = tasks
+    let code = outcomes
         .iter()
-        .find(|t| t.name == name)
-        .ok_or_else(|| MuuError::TaskNotFound {
-            name: name.to_string(),
-        })?;
-
-    let resolved = resolve_args(&task.def.args, raw_args)?;
-    let cmd = expand_command(&task.def.cmd, &resolved);
-    eprintln!("\x1b[35m$\x1b[0m {cmd}");
-    Ok(runner::execute(&cmd))
+        .find_map(|o| match o {
+            Outcome::Failed(code) => Some(*code),
+            Outcome::Error { code, .. } => Some(*code),
+            _ => None,
+        })
+        .unwrap_or(0);
+    let results: Vec<(Cell, Outcome)> = cells.into_iter().zip(outcomes).collect();
+    eprintln!("\n{}", matrix::render_table(matrix, &results));
+    Ok(code)
+}
+
+/// Run one matrix cell. In parallel mode output lines are prefixed with the
+/// cell label; otherwise each cell gets a header line.
+fn run_cell(task: &ResolvedTask, raw_args: &[String], cell: &Cell, opts: &RunOptions) -> Outcome {
+    let label = matrix::label(cell);
+
+    // Matrix values are checked and expanded like given values
+    let resolved = task::resolve_cell_args(&task.def.args, raw_args, task.def.strict_args, cell);
+    let resolved = match resolved {
+        Ok(resolved) => resolved,
+        Err(e) => return error(e),
+    };
+
+    if !opts.parallel {
+        eprintln!("\x1b[1m▸ {} [{label}]\x1b[22m", task.name);
+    }
+    if !opts.force && is_up_to_date(task, &resolved) {
+        return Outcome::UpToDate;
+    }
+    let (echo, command) = match prepare(task, &resolved) {
+        Ok(prepared) => prepared,
+        Err(e) => return error(e),
+    };
+
+    let code = if opts.parallel {
+        eprintln!("[{label}] \x1b[35m$\x1b[0m {echo}");
+        runner::status_labeled(command, &label)
+    } else {
+        eprintln!("\x1b[35m$\x1b[0m {echo}");
+        runner::status(command)
+    };
+    if code == 0 {
+        Outcome::Passed
+    } else {
+        Outcome::Failed(code)
+    }
+}
+
+fn error(e: MuuError) -> Outcome {
+    Outcome::Error {
+        message: e.to_string(),
+        code: e.exit_code(),
+    }
+}
+
+/// Resolve args with the syntax the task asks for.
+fn resolve(
+    def: &TaskDef,
+    defined: &IndexMap<String, ArgDef>,
+    raw_args: &[String],
+) -> Result<IndexMap<String, String>, MuuError> {
+    if def.strict_args {
+        resolve_args_strict(defined, raw_args)
+    } else {
+        resolve_args(defined, raw_args)
+    }
+}
+
+/// A task is up to date when it has status checks and all of them pass.
+pub fn is_up_to_date(task: &ResolvedTask, resolved: &IndexMap<String, String>) -> bool {
+    !task.def.status.is_empty()
+        && task
+            .def
+            .status
+            .iter()
+            .all(|check| runner::succeeds(&expand_command(check, resolved)))
+}
+
+pub fn report_up_to_date(task: &ResolvedTask) {
+    eprintln!("\x1b[32mtask '{}' is up to date\x1b[0m", task.name);
+}
+
+/// Run a task whose args are already resolved: check its preconditions,
+/// then echo and execute the expanded command.
+pub fn execute_task(
+    task: &ResolvedTask,
+    resolved: &IndexMap<String, String>,
+) -> Result<i32, MuuError> {
+    let (echo, command) = prepare(task, resolved)?;
+    eprintln!("\x1b[35m$\x1b[0m {echo}");
+    Ok(runner::status(command))
+}
+
+/// Check preconditions and build the process to run, along with the line
+/// to echo. Script tasks run their file directly with the resolved args as
+/// argv; everything else runs the expanded `cmd` through the shell.
+fn prepare(
+    task: &ResolvedTask,
+    resolved: &IndexMap<String, String>,
+) -> Result<(String, Command), MuuError> {
+    for pre in &task.def.preconditions {
+        let check = expand_command(&pre.sh, resolved);
+        if !runner::succeeds(&check) {
+            let message = pre
+                .msg
+                .clone()
+                .unwrap_or_else(|| format!("precondition failed: {check}"));
+            return Err(MuuError::PreconditionFailed { message });
+        }
+    }
+
+    if let Some(ref script) = task.def.script {
+        // Variadic and pass-through args hold shell-quoted words; the
+        // script gets each one as a separate argv entry
+        let args: Vec<String> = resolved
+            .iter()
+            .flat_map(|(name, value)| match task.def.args.get(name) {
+                Some(arg) if !arg.variadic => vec![value.clone()],
+                _ => split_words(value).unwrap_or_default(),
+            })
+            .collect();
+        let echo = std::iter::once(script.display().to_string())
+            .chain(args.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ");
+        return Ok((echo, runner::file(script, &args)));
+    }
+    let cmd = expand_command(&task::command_with_passthrough(&task.def), resolved);
+    let command = runner::shell(&cmd);
+    Ok((cmd, command))
 }
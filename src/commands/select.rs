@@ -2,47 +2,101 @@ use std::fmt;
 
 use indexmap::IndexMap;
 use inquire::validator::Validation;
-use inquire::{InquireError, Select, Text};
+use inquire::{Confirm, InquireError, Select, Text};
 
-use crate::config::{ArgDef, ResolvedTask};
+use crate::choices;
+use crate::commands::run::{self, RunOptions};
+use crate::config::{ArgDef, ArgType, ResolvedTask};
+use crate::defaults;
 use crate::error::MuuError;
-use crate::runner;
-use crate::task::expand_command;
+use crate::history::History;
+use crate::namespace::{self, Row};
+use crate::requires;
+use crate::task;
 
 struct TaskOption<'a> {
     task: &'a ResolvedTask,
+    indent: usize,
     max_name: usize,
 }
 
 const DIM: &str = "\x1b[2m";
 const RESET_DIM: &str = "\x1b[22m";
+const BOLD: &str = "\x1b[1m";
 
 impl fmt::Display for TaskOption<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let desc = self.task.def.description.as_deref().unwrap_or("");
-        if desc.is_empty() {
-            write!(f, "{}", self.task.name)
-        } else {
-            write!(
-                f,
-                "{:<name_w$}  {DIM}{desc}{RESET_DIM}",
-                self.task.name,
-                name_w = self.max_name,
-            )
+        let name = format!("{}{}", "  ".repeat(self.indent), self.task.label());
+        // Tags are part of the display text so the fuzzy filter matches them
+        let tags = self.task.tags_label();
+        let detail = match (desc.is_empty(), tags.is_empty()) {
+            (true, true) => return write!(f, "{name}"),
+            (false, true) => desc.to_string(),
+            (true, false) => tags,
+            (false, false) => format!("{desc} {tags}"),
+        };
+        write!(
+            f,
+            "{name:<name_w$}  {DIM}{detail}{RESET_DIM}",
+            name_w = self.max_name,
+        )
+    }
+}
+
+/// Selector entry. Picking a namespace header narrows the list to it.
+enum Entry<'a> {
+    Namespace { name: &'a str, indent: usize },
+    Task(TaskOption<'a>),
+}
+
+impl fmt::Display for Entry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Namespace { name, indent } => {
+                write!(f, "{}{BOLD}{name}:{RESET_DIM}", "  ".repeat(*indent))
+            }
+            Entry::Task(option) => option.fmt(f),
         }
     }
 }
 
-pub fn select(tasks: &[ResolvedTask]) -> Result<i32, MuuError> {
-    if tasks.is_empty() {
+/// Let the user pick one of `listed` and answer its args. A deprecated pick
+/// is redirected among all `tasks`, like `muu <task>` does. With
+/// `opts.history`, the answers are remembered per project and offered again
+/// next time.
+pub fn select(
+    listed: &[ResolvedTask],
+    tasks: &[ResolvedTask],
+    opts: &RunOptions,
+) -> Result<i32, MuuError> {
+    if listed.is_empty() {
         return Err(MuuError::NoTasksDefined);
     }
 
-    let max_name = tasks.iter().map(|t| t.name.len()).max().unwrap_or(0);
-
-    let options: Vec<TaskOption> = tasks
+    let rows = namespace::tree(listed);
+    let max_name = rows
         .iter()
-        .map(|task| TaskOption { task, max_name })
+        .filter_map(|row| match row {
+            Row::Task { task, depth } => Some(depth * 2 + task.label().len()),
+            Row::Namespace { .. } => None,
+        })
+        .max()
+        .unwrap_or(0);
+
+    let options: Vec<Entry> = rows
+        .into_iter()
+        .map(|row| match row {
+            Row::Namespace { name, depth } => Entry::Namespace {
+                name,
+                indent: depth,
+            },
+            Row::Task { task, depth } => Entry::Task(TaskOption {
+                task,
+                indent: depth,
+                max_name,
+            }),
+        })
         .collect();
 
     let result = Select::new("Select a task:", options)
@@ -50,7 +104,13 @@ pub fn select(tasks: &[ResolvedTask]) -> Result<i32, MuuError> {
         .prompt();
 
     match result {
-        Ok(selected) => execute_selected(selected.task),
+        Ok(Entry::Task(selected)) => {
+            let task = run::redirect(selected.task, tasks, opts.strict)?;
+            execute_selected(task, opts)
+        }
+        Ok(Entry::Namespace { name, .. }) => {
+            select(&namespace::in_namespace(listed, name), tasks, opts)
+        }
         Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => Ok(1),
         Err(e) => {
             eprintln!("error: {e}");
@@ -64,55 +124,91 @@ enum PromptResult {
     Cancelled,
 }
 
-fn execute_selected(task: &ResolvedTask) -> Result<i32, MuuError> {
+fn execute_selected(task: &ResolvedTask, opts: &RunOptions) -> Result<i32, MuuError> {
+    requires::check(&task.def.requires)?;
+
     if task.def.args.is_empty() {
-        eprintln!("\x1b[35m$\x1b[0m {}", task.def.cmd);
-        return Ok(runner::execute(&task.def.cmd));
+        return run_resolved(task, &IndexMap::new(), opts);
     }
 
-    eprintln!("{DIM}# {}{RESET_DIM}", task.def.cmd);
+    let preview = match task.def.script {
+        Some(ref path) => path.display().to_string(),
+        None => task.def.cmd.clone(),
+    };
+    eprintln!("{DIM}# {preview}{RESET_DIM}");
 
-    match prompt_args(&task.def.args)? {
+    let mut args = task.def.args.clone();
+    choices::fill(&mut args);
+    let mut history = if opts.history {
+        History::load()
+    } else {
+        History::default()
+    };
+    match prompt_args(&task.name, &args, &mut history)? {
         PromptResult::Resolved(resolved) => {
-            let cmd = expand_command(&task.def.cmd, &resolved);
-            eprintln!("\x1b[35m$\x1b[0m {cmd}");
-            Ok(runner::execute(&cmd))
+            history.save();
+            run_resolved(task, &resolved, opts)
         }
         PromptResult::Cancelled => Ok(1),
     }
 }
 
-fn prompt_args(defined: &IndexMap<String, ArgDef>) -> Result<PromptResult, MuuError> {
+/// Run the picked task unless its status checks say it is up to date.
+fn run_resolved(
+    task: &ResolvedTask,
+    resolved: &IndexMap<String, String>,
+    opts: &RunOptions,
+) -> Result<i32, MuuError> {
+    if !opts.force && run::is_up_to_date(task, resolved) {
+        run::report_up_to_date(task);
+        return Ok(0);
+    }
+    run::execute_task(task, resolved)
+}
+
+fn prompt_args(
+    task: &str,
+    defined: &IndexMap<String, ArgDef>,
+    history: &mut History,
+) -> Result<PromptResult, MuuError> {
     let mut resolved: IndexMap<String, String> = IndexMap::new();
+    // Computed defaults that refer to args asked later
+    let mut pending = Vec::new();
 
     for (name, arg) in defined {
-        let is_required = arg.default.is_empty() && !arg.optional;
-        let prompt_message = if arg.default.is_empty() {
-            format!("{name}:")
+        let arg = &with_default(name, arg, defined, &resolved)?;
+        // A set env variable is a deliberate choice, so it beats the history
+        let past = if arg.forget || arg.env_value().is_some() {
+            Vec::new()
         } else {
-            format!("{name}[{}]:", arg.default)
+            history.values(task, name).to_vec()
         };
-
-        let mut text_prompt = Text::new(&prompt_message);
-        if is_required {
-            text_prompt = text_prompt.with_validator(|input: &str| {
+        // The value to use and the answer to remember
+        let answer = match arg.kind {
+            ArgType::Flag => prompt_flag(name, arg, past.first()).map(|set| {
+                Ok::<_, MuuError>(Some((arg.flag_text(set), remembered(arg, set.to_string()))))
+            }),
+            _ => prompt_arg(name, arg, &past).map(|input| {
+                if input.is_empty() && arg.default.is_empty() && arg.computed.is_some() {
+                    return Ok(None);
+                }
                 if input.is_empty() {
-                    Ok(Validation::Invalid("This argument is required".into()))
-                } else {
-                    Ok(Validation::Valid)
+                    return Ok(Some((arg.default.clone(), String::new())));
                 }
-            });
-        }
-
-        match text_prompt.prompt() {
-            Ok(value) => {
-                let value = if value.is_empty() && !arg.default.is_empty() {
-                    arg.default.clone()
-                } else {
-                    value
-                };
-                resolved.insert(name.clone(), value);
-            }
+                let value = check_input(name, arg, &input)?;
+                Ok(Some((value, remembered(arg, input))))
+            }),
+        };
+        match answer {
+            Ok(value) => match value? {
+                Some((value, answered)) => {
+                    if !arg.forget && !answered.is_empty() {
+                        history.record(task, name, &answered);
+                    }
+                    resolved.insert(name.clone(), value);
+                }
+                None => pending.push(name.as_str()),
+            },
             Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
                 return Ok(PromptResult::Cancelled);
             }
@@ -122,14 +218,140 @@ fn prompt_args(defined: &IndexMap<String, ArgDef>) -> Result<PromptResult, MuuEr
             }
         }
     }
+    defaults::compute(defined, &pending, &mut resolved)?;
 
     Ok(PromptResult::Resolved(resolved))
 }
 
+/// The arg with the default to offer: its `env` variable when set, else its
+/// computed default when the args that needs are already answered.
+fn with_default(
+    name: &str,
+    arg: &ArgDef,
+    defined: &IndexMap<String, ArgDef>,
+    resolved: &IndexMap<String, String>,
+) -> Result<ArgDef, MuuError> {
+    let mut arg = arg.clone();
+    let value = match (arg.env_value(), &arg.computed) {
+        (Some(value), _) => value,
+        (None, Some(computed)) => {
+            defaults::evaluate(name, computed, defined, resolved)?.unwrap_or_default()
+        }
+        (None, None) => return Ok(arg),
+    };
+    if !value.is_empty() {
+        arg.default = check_input(name, &arg, &value)?;
+    }
+    Ok(arg)
+}
+
+/// The part of an answer to remember. Defaults from env variables or
+/// commands may be private, so taking one over is not remembered, only
+/// what was typed or picked otherwise.
+fn remembered(arg: &ArgDef, answer: String) -> String {
+    let offered = arg.env_value().is_some() || arg.computed.is_some();
+    if offered && answer == arg.default {
+        String::new()
+    } else {
+        answer
+    }
+}
+
+/// Ask yes or no for a flag, defaulting to the last answer or its default.
+fn prompt_flag(name: &str, arg: &ArgDef, last: Option<&String>) -> Result<bool, InquireError> {
+    let message = format!("{name}?");
+    let set = last.map_or(arg.default == "true", |v| v == "true");
+    let mut confirm = Confirm::new(&message).with_default(set);
+    if let Some(ref help) = arg.help {
+        confirm = confirm.with_help_message(help);
+    }
+    confirm.prompt()
+}
+
+/// Ask for one arg: a picker with the last answer or the default
+/// preselected when the arg has choices, free text checked against its type
+/// otherwise, prefilled with the last answer and completing earlier ones.
+/// An empty answer means "use the default".
+fn prompt_arg(name: &str, arg: &ArgDef, past: &[String]) -> Result<String, InquireError> {
+    let is_required = arg.is_required();
+    let prompt_message = if arg.default.is_empty() || !arg.choices.is_empty() {
+        format!("{name}:")
+    } else {
+        format!("{name}[{}]:", arg.default)
+    };
+
+    if !arg.choices.is_empty() {
+        let preselected = past.first().unwrap_or(&arg.default);
+        let cursor = arg
+            .choices
+            .iter()
+            .position(|c| c == preselected)
+            .or_else(|| arg.choices.iter().position(|c| *c == arg.default))
+            .unwrap_or(0);
+        let mut select =
+            Select::new(&prompt_message, arg.choices.clone()).with_starting_cursor(cursor);
+        if let Some(ref help) = arg.help {
+            select = select.with_help_message(help);
+        }
+        return select.prompt();
+    }
+
+    let mut text_prompt = Text::new(&prompt_message);
+    if let Some(ref help) = arg.help {
+        text_prompt = text_prompt.with_help_message(help);
+    }
+    if let Some(last) = past.first() {
+        let earlier = past.to_vec();
+        text_prompt = text_prompt
+            .with_initial_value(last)
+            .with_autocomplete(move |input: &str| {
+                Ok(earlier
+                    .iter()
+                    .filter(|v| v.contains(input))
+                    .cloned()
+                    .collect())
+            });
+    }
+    let (arg_name, checked) = (name.to_string(), arg.clone());
+    text_prompt
+        .with_validator(move |input: &str| {
+            if input.is_empty() {
+                return Ok(if is_required {
+                    Validation::Invalid("This argument is required".into())
+                } else {
+                    Validation::Valid
+                });
+            }
+            Ok(match check_input(&arg_name, &checked, input) {
+                Ok(_) => Validation::Valid,
+                Err(e) => Validation::Invalid(e.to_string().into()),
+            })
+        })
+        .prompt()
+}
+
+/// Check a typed answer. A variadic arg takes shell-style words, e.g.
+/// `a.txt "my file.txt"`, and resolves to them quoted like on the CLI.
+fn check_input(name: &str, arg: &ArgDef, input: &str) -> Result<String, MuuError> {
+    if !arg.variadic {
+        return task::check_value(name, arg, input);
+    }
+    let Some(words) = task::split_words(input) else {
+        return Err(MuuError::InvalidArgValue {
+            name: name.to_string(),
+            value: input.to_string(),
+            expected: "closed quotes".to_string(),
+        });
+    };
+    let raw: Vec<String> = words.into_iter().map(|w| format!("--{name}={w}")).collect();
+    let defined = IndexMap::from([(name.to_string(), arg.clone())]);
+    task::resolve_args(&defined, &raw).map(|mut r| r.swap_remove(name).unwrap_or_default())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{TaskDef, TaskSource};
+    use crate::config::{Computed, TaskDef, TaskSource};
 
     #[test]
     fn execute_selected_no_args_task() {
@@ -138,11 +360,48 @@ mod tests {
             def: TaskDef {
                 cmd: "echo hello".to_string(),
                 description: Some("Say hello".to_string()),
-                args: IndexMap::new(),
+                ..Default::default()
             },
             source: TaskSource::Local,
         };
-        let result = execute_selected(&task).unwrap();
+        let result = execute_selected(&task, &RunOptions::default()).unwrap();
         assert_eq!(result, 0);
     }
+
+    #[test]
+    fn execute_selected_skips_up_to_date_task() {
+        let task = ResolvedTask {
+            name: "build".to_string(),
+            def: TaskDef {
+                cmd: "exit 1".to_string(),
+                status: vec!["true".to_string()],
+                ..Default::default()
+            },
+            source: TaskSource::Local,
+        };
+        assert_eq!(execute_selected(&task, &RunOptions::default()).unwrap(), 0);
+
+        let forced = RunOptions {
+            force: true,
+            ..Default::default()
+        };
+        assert_eq!(execute_selected(&task, &forced).unwrap(), 1);
+    }
+
+    #[test]
+    fn computed_defaults_are_not_remembered() {
+        let computed = ArgDef {
+            default: "alice".to_string(),
+            computed: Some(Computed::Sh("whoami".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(remembered(&computed, "alice".to_string()), "");
+        assert_eq!(remembered(&computed, "bob".to_string()), "bob");
+
+        let plain = ArgDef {
+            default: "dev".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(remembered(&plain, "dev".to_string()), "dev");
+    }
 }
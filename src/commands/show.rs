@@ -0,0 +1,24 @@
+use std::path::Path;
+
+use crate::config::{self, ResolvedTask};
+use crate::docs;
+use crate::error::MuuError;
+
+/// Print the usage, arguments and command of one task.
+pub fn show(name: &str, tasks: &[ResolvedTask], base: &Path) -> Result<(), MuuError> {
+    let task = config::find_task(tasks, name).ok_or_else(|| MuuError::TaskNotFound {
+        name: name.to_string(),
+    })?;
+    print!("{}", docs::show(task, base));
+    Ok(())
+}
+
+/// Whether `muu <task> ... --help` asks for the task's help rather than
+/// passing `--help` on. A task with its own `help` arg, or `--help` after
+/// `--`, keeps the old meaning.
+pub fn wants_help(name: &str, args: &[String], tasks: &[ResolvedTask]) -> bool {
+    args.iter()
+        .take_while(|a| *a != "--")
+        .any(|a| a == "--help")
+        && config::find_task(tasks, name).is_some_and(|t| !t.def.args.contains_key("help"))
+}
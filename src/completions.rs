@@ -2,7 +2,10 @@ use std::env;
 
 use clap_complete::engine::{CompletionCandidate, ValueCandidates};
 
-use crate::config;
+use crate::choices;
+use crate::cli;
+use crate::config::{self, ArgDef, ArgType, ResolvedTask};
+use crate::namespace::SEPARATOR;
 
 #[derive(Clone, Debug)]
 pub struct TaskCandidates;
@@ -16,16 +19,184 @@ impl ValueCandidates for TaskCandidates {
             Err(_) => return Vec::new(),
         };
 
-        tasks
-            .into_iter()
-            .map(|task| {
-                let candidate = CompletionCandidate::new(task.name);
-                if let Some(desc) = task.def.description {
-                    candidate.help(Some(desc.into()))
-                } else {
-                    candidate
+        let (words, index) = completion_words();
+        let current = words.get(index).cloned().unwrap_or_default();
+        if let Some((name, given)) = task_words(&words[..index.min(words.len())])
+            && let Some(task) = tasks.iter().find(|t| t.matches(name))
+        {
+            return arg_candidates(task, given, &current);
+        }
+
+        let mut candidates: Vec<CompletionCandidate> = Vec::new();
+        let mut namespaces: Vec<&str> = Vec::new();
+        for task in &tasks {
+            if let Some(ns) = next_namespace(&task.name, &current) {
+                if !namespaces.contains(&ns) {
+                    namespaces.push(ns);
+                    candidates.push(CompletionCandidate::new(ns).help(Some("namespace".into())));
                 }
+                continue;
+            }
+
+            let candidate = CompletionCandidate::new(&task.name);
+            candidates.push(if let Some(ref desc) = task.def.description {
+                candidate.help(Some(desc.clone().into()))
+            } else {
+                candidate
+            });
+            for alias in &task.def.aliases {
+                candidates.push(
+                    CompletionCandidate::new(alias)
+                        .help(Some(format!("alias for {}", task.name).into())),
+                );
+            }
+        }
+        candidates
+    }
+}
+
+/// The words of the command line and the index of the one being completed.
+/// `ValueCandidates` is not given them, so recover them from the
+/// `CompleteEnv` invocation (`muu -- muu <words...>`).
+fn completion_words() -> (Vec<String>, usize) {
+    let words: Vec<String> = env::args().skip_while(|a| a != "--").skip(1).collect();
+    let index = env::var("_CLAP_COMPLETE_INDEX")
+        .ok()
+        .and_then(|i| i.parse().ok())
+        .unwrap_or(words.len().saturating_sub(1));
+    (words, index)
+}
+
+/// The task name among the words before the cursor and the words after it,
+/// or `None` while the task name itself is being completed.
+fn task_words(words: &[String]) -> Option<(&str, &[String])> {
+    let i = cli::command_index(words)?;
+    Some((&words[i], &words[i + 1..]))
+}
+
+/// Candidates for the next arg of `task`: the choices of the positional
+/// arg at the cursor, or `--name=choice` for named args.
+fn arg_candidates(
+    task: &ResolvedTask,
+    given: &[String],
+    current: &str,
+) -> Vec<CompletionCandidate> {
+    let with_help = |candidate: CompletionCandidate, help: &Option<String>| match help {
+        Some(help) => candidate.help(Some(help.clone().into())),
+        None => candidate,
+    };
+    if current.starts_with('-') {
+        return task
+            .def
+            .args
+            .iter()
+            .flat_map(|(name, arg)| {
+                let words = if arg.kind == ArgType::Flag {
+                    vec![format!("--{name}"), format!("--no-{name}")]
+                } else {
+                    choices(arg)
+                        .into_iter()
+                        .map(|choice| format!("--{name}={choice}"))
+                        .collect()
+                };
+                words
+                    .into_iter()
+                    .map(move |word| with_help(CompletionCandidate::new(word), &arg.help))
             })
-            .collect()
+            .collect();
+    }
+    // Flags are never positional, so they don't take up a slot
+    let position = given.iter().filter(|w| !w.starts_with('-')).count();
+    let mut positional = task
+        .def
+        .args
+        .values()
+        .filter(|arg| arg.kind != ArgType::Flag);
+    match positional.nth(position) {
+        Some(arg) => choices(arg)
+            .into_iter()
+            .map(|choice| with_help(CompletionCandidate::new(choice), &arg.help))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn choices(arg: &ArgDef) -> Vec<String> {
+    match arg.choices_cmd {
+        Some(ref cmd) => choices::for_completion(cmd),
+        None => arg.choices.clone(),
+    }
+}
+
+/// For level-by-level completion: if `name` lies more than one namespace
+/// level below what has been typed, return the next namespace to offer
+/// instead (e.g. `db` for `db:migrate` while `current` is `d`).
+fn next_namespace<'a>(name: &'a str, current: &str) -> Option<&'a str> {
+    let rest = name.strip_prefix(current)?;
+    // A separator right at the cursor counts as already typed
+    let offset = rest.chars().next()?.len_utf8();
+    let end = rest[offset..].find(SEPARATOR)? + offset;
+    Some(&name[..current.len() + end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn task_words_skips_global_flags() {
+        let w = words("muu -l -t ci deploy prod");
+        assert_eq!(task_words(&w), Some(("deploy", &w[5..])));
+        let w = words("muu --matrix env=a,b test");
+        assert_eq!(task_words(&w), Some(("test", &w[4..])));
+        assert_eq!(task_words(&words("muu -l")), None);
+    }
+
+    #[test]
+    fn arg_candidates_offer_choices() {
+        let task: ResolvedTask = ResolvedTask {
+            name: "deploy".to_string(),
+            def: toml::from_str(
+                r#"
+cmd = "true"
+args.dir = "."
+args.env = { choices = ["dev", "prod"] }
+args.force = { type = "flag" }
+"#,
+            )
+            .unwrap(),
+            source: config::TaskSource::Local,
+        };
+        let values = |given: &str, current: &str| -> Vec<String> {
+            arg_candidates(&task, &words(given), current)
+                .iter()
+                .map(|c| c.get_value().to_string_lossy().into_owned())
+                .collect()
+        };
+        assert!(values("", "").is_empty());
+        assert_eq!(values("./dist", ""), vec!["dev", "prod"]);
+        assert_eq!(
+            values("", "--"),
+            vec!["--env=dev", "--env=prod", "--force", "--no-force"]
+        );
+        assert!(values("./dist dev", "").is_empty());
+    }
+
+    #[test]
+    fn next_namespace_levels() {
+        assert_eq!(next_namespace("db:migrate", ""), Some("db"));
+        assert_eq!(next_namespace("db:migrate", "d"), Some("db"));
+        assert_eq!(next_namespace("db:migrate", "db"), None);
+        assert_eq!(next_namespace("db:migrate", "db:"), None);
+        assert_eq!(
+            next_namespace("cloud:aws:deploy", "cloud"),
+            Some("cloud:aws")
+        );
+        assert_eq!(next_namespace("build", ""), None);
+        assert_eq!(next_namespace("build", "x"), None);
     }
 }
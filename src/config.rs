@@ -1,34 +1,412 @@
 use std::path::{Path, PathBuf};
 
 use indexmap::IndexMap;
-use serde::de::Deserializer;
 use serde::Deserialize;
+use serde::de::{DeserializeSeed, Deserializer, Error as _, IgnoredAny, MapAccess, Visitor};
 
+use crate::defaults;
 use crate::error::MuuError;
+use crate::matrix::Matrix;
+use crate::namespace;
+use crate::providers::Provider;
+use crate::requires::Requirement;
+use crate::scripts;
 
 // ---------- Arg definition ----------
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct ArgDef {
+    /// Plain default; empty when there is none or it is `computed`.
     pub default: String,
+    /// Default worked out when the task runs, from other args or a command.
+    pub computed: Option<Computed>,
     pub optional: bool,
+    pub kind: ArgType,
+    pub help: Option<String>,
+    /// Inclusive bounds for `int` and `float` args.
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// For `path` args: the path has to exist when the task runs.
+    pub must_exist: bool,
+    /// Allowed values; required for `enum` args, optional for the others.
+    pub choices: Vec<String>,
+    /// Command whose output lines are the allowed values, filled into
+    /// `choices` by `choices::fill`.
+    pub choices_cmd: Option<String>,
+    /// Collects all remaining positional values; only the last arg.
+    pub variadic: bool,
+    /// Fewest values a variadic arg accepts.
+    pub min_count: usize,
+    /// Single-letter alias, e.g. `-b` for `--bucket`.
+    pub short: Option<char>,
+    /// What a `flag` arg expands to when set and when not.
+    pub on: Option<String>,
+    pub off: Option<String>,
+    /// Environment variable used when the arg isn't given, before `default`.
+    pub env: Option<String>,
+    /// `remember = false`: values are never saved to or offered from the
+    /// selector's history, e.g. for secrets.
+    pub forget: bool,
+}
+
+impl ArgDef {
+    /// The text a `flag` arg expands to: `on`/`off`, or `true`/`false`.
+    pub fn flag_text(&self, set: bool) -> String {
+        let (text, fallback) = if set {
+            (&self.on, "true")
+        } else {
+            (&self.off, "false")
+        };
+        text.clone().unwrap_or_else(|| fallback.to_string())
+    }
+
+    /// Whether the arg has to be given: no default of any kind and not optional.
+    pub fn is_required(&self) -> bool {
+        self.default.is_empty() && self.computed.is_none() && !self.optional
+    }
+
+    /// The value of the arg's `env` variable, if it is set and not empty.
+    pub fn env_value(&self) -> Option<String> {
+        let var = self.env.as_deref()?;
+        std::env::var(var).ok().filter(|v| !v.is_empty())
+    }
+}
+
+/// A default computed by `defaults::compute` once explicit values are known.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Computed {
+    /// Text with `{{ arg }}` and `{{ $VAR }}` placeholders.
+    Template(String),
+    /// Command whose trimmed output is the default. Placeholders work as in
+    /// templates, with values shell-quoted.
+    Sh(String),
+}
+
+impl Computed {
+    pub fn text(&self) -> &str {
+        match self {
+            Computed::Template(text) | Computed::Sh(text) => text,
+        }
+    }
+}
+
+impl std::fmt::Display for Computed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Computed::Template(text) => write!(f, "{text}"),
+            Computed::Sh(cmd) => write!(f, "output of `{cmd}`"),
+        }
+    }
+}
+
+/// Value type of an arg, checked by `task::resolve_args`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgType {
+    #[default]
+    String,
+    Int,
+    Float,
+    Bool,
+    Path,
+    Enum,
+    /// Set by `--name`, cleared by `--no-name`; expands to `on`/`off`.
+    Flag,
+}
+
+impl std::fmt::Display for ArgType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ArgType::String => "string",
+            ArgType::Int => "int",
+            ArgType::Float => "float",
+            ArgType::Bool => "bool",
+            ArgType::Path => "path",
+            ArgType::Enum => "enum",
+            ArgType::Flag => "flag",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The `name = { type = "int", default = 3, ... }` form of an arg.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ArgTable {
+    #[serde(default, rename = "type")]
+    kind: ArgType,
+    #[serde(default)]
+    default: Option<toml::Value>,
+    #[serde(default)]
+    optional: bool,
+    #[serde(default)]
+    help: Option<String>,
+    #[serde(default)]
+    min: Option<f64>,
+    #[serde(default)]
+    max: Option<f64>,
+    #[serde(default)]
+    must_exist: bool,
+    #[serde(default)]
+    choices: Vec<toml::Value>,
+    #[serde(default)]
+    choices_cmd: Option<String>,
+    #[serde(default)]
+    variadic: bool,
+    #[serde(default)]
+    min_count: Option<usize>,
+    #[serde(default)]
+    short: Option<String>,
+    #[serde(default)]
+    on: Option<String>,
+    #[serde(default)]
+    off: Option<String>,
+    #[serde(default)]
+    env: Option<String>,
+    #[serde(default)]
+    remember: Option<bool>,
 }
 
 fn deserialize_args<'de, D>(deserializer: D) -> Result<IndexMap<String, ArgDef>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let raw: IndexMap<String, String> = IndexMap::deserialize(deserializer)?;
-    let mut args = IndexMap::new();
-    for (key, default) in raw {
-        let (default, optional) = if default == "?" {
-            (String::new(), true)
-        } else {
-            (default, false)
+    let raw: IndexMap<String, toml::Value> = IndexMap::deserialize(deserializer)?;
+    let last = raw.len().saturating_sub(1);
+    let mut shorts = Vec::new();
+    let mut args = raw
+        .into_iter()
+        .enumerate()
+        .map(|(i, (key, value))| {
+            let arg = match value {
+                toml::Value::String(default) => Ok(shorthand_arg(default)),
+                toml::Value::Table(table) => table_arg(table),
+                other => Err(format!(
+                    "expected a default string or a table, found {}",
+                    other.type_str()
+                )),
+            }
+            .and_then(|arg| {
+                if arg.variadic && i != last {
+                    return Err("only the last arg can be variadic".to_string());
+                }
+                if let Some(short) = arg.short {
+                    if shorts.contains(&short) {
+                        return Err(format!("short alias `-{short}` is already taken"));
+                    }
+                    shorts.push(short);
+                }
+                Ok(arg)
+            });
+            arg.map(|arg| (key.clone(), arg))
+                .map_err(|e| D::Error::custom(format!("arg `{key}`: {e}")))
+        })
+        .collect::<Result<IndexMap<_, _>, _>>()?;
+
+    find_templates(&mut args).map_err(D::Error::custom)?;
+    Ok(args)
+}
+
+/// Matrix values are scalars like arg defaults; every key needs at least
+/// one value.
+fn deserialize_matrix<'de, D>(deserializer: D) -> Result<Matrix, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw: IndexMap<String, Vec<toml::Value>> = IndexMap::deserialize(deserializer)?;
+    raw.into_iter()
+        .map(|(key, values)| {
+            if values.is_empty() {
+                return Err(D::Error::custom(format!(
+                    "matrix `{key}`: needs at least one value"
+                )));
+            }
+            let values = values
+                .into_iter()
+                .map(scalar)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| D::Error::custom(format!("matrix `{key}`: {e}")))?;
+            Ok((key, values))
+        })
+        .collect()
+}
+
+/// Interpret a `name = "default"` value: `"?"` is optional with no default,
+/// `"..."` takes all remaining values.
+pub fn shorthand_arg(default: String) -> ArgDef {
+    if default == "?" {
+        ArgDef {
+            optional: true,
+            ..Default::default()
+        }
+    } else if default == "..." {
+        ArgDef {
+            optional: true,
+            variadic: true,
+            ..Default::default()
+        }
+    } else {
+        ArgDef {
+            default,
+            ..Default::default()
+        }
+    }
+}
+
+/// Make defaults with `{{ arg }}` or `{{ $VAR }}` placeholders computed,
+/// once all arg names are known. Other defaults are plain, with `{{{{`
+/// unescaped to `{{`. A `{{ name }}` naming no arg is an error.
+pub fn find_templates(args: &mut IndexMap<String, ArgDef>) -> Result<(), String> {
+    let names: Vec<String> = args.keys().cloned().collect();
+    let is_arg = |name: &str| names.iter().any(|n| n == name);
+    for (key, arg) in args.iter_mut() {
+        let text = match arg.computed {
+            Some(ref computed) => computed.text(),
+            None => &arg.default,
         };
-        args.insert(key, ArgDef { default, optional });
+        if let Some(unknown) = defaults::unknown_reference(text, &is_arg) {
+            return Err(format!(
+                "arg `{key}`: default refers to unknown arg `{unknown}`; write `{{{{{{{{` for a literal `{{{{`"
+            ));
+        }
+        if arg.computed.is_some() || !arg.default.contains("{{") {
+            continue;
+        }
+        match defaults::literal(&arg.default, &is_arg) {
+            Some(plain) => arg.default = plain,
+            None => {
+                arg.computed = Some(Computed::Template(std::mem::take(&mut arg.default)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Split a table-form `default` into a plain and a computed part.
+fn default_value(value: Option<toml::Value>) -> Result<(String, Option<Computed>), String> {
+    match value {
+        None => Ok((String::new(), None)),
+        Some(toml::Value::Table(mut table)) => match (table.remove("sh"), table.is_empty()) {
+            (Some(toml::Value::String(cmd)), true) => Ok((String::new(), Some(Computed::Sh(cmd)))),
+            _ => Err("a table `default` takes only `sh = \"command\"`".to_string()),
+        },
+        Some(value) => scalar(value).map(|default| (default, None)),
+    }
+}
+
+fn table_arg(table: toml::Table) -> Result<ArgDef, String> {
+    let raw: ArgTable = toml::Value::Table(table)
+        .try_into()
+        .map_err(|e: toml::de::Error| e.message().to_string())?;
+    let (mut default, computed) = default_value(raw.default)?;
+    if computed.is_some() && raw.kind == ArgType::Flag {
+        return Err("type flag can't have a computed default".to_string());
+    }
+    if raw.kind == ArgType::Flag {
+        default = match default.as_str() {
+            "" | "false" => "false".to_string(),
+            "true" => default,
+            _ => {
+                return Err(format!(
+                    "flag default must be true or false, not `{default}`"
+                ));
+            }
+        };
+    }
+    let choices = raw
+        .choices
+        .into_iter()
+        .map(scalar)
+        .collect::<Result<Vec<_>, _>>()?;
+    let numeric = matches!(raw.kind, ArgType::Int | ArgType::Float);
+    if (raw.min.is_some() || raw.max.is_some()) && !numeric {
+        return Err(format!(
+            "`min` and `max` need type int or float, not {}",
+            raw.kind
+        ));
+    }
+    if let (Some(min), Some(max)) = (raw.min, raw.max)
+        && min > max
+    {
+        return Err(format!("`min` ({min}) is greater than `max` ({max})"));
+    }
+    if raw.min_count.is_some() && !raw.variadic {
+        return Err("`min_count` needs `variadic = true`".to_string());
+    }
+    if raw.variadic && (!default.is_empty() || computed.is_some() || raw.optional) {
+        return Err(
+            "variadic args can't have `default` or `optional`; use `min_count`".to_string(),
+        );
+    }
+    if raw.must_exist && raw.kind != ArgType::Path {
+        return Err(format!("`must_exist` needs type path, not {}", raw.kind));
+    }
+    if !choices.is_empty() && raw.choices_cmd.is_some() {
+        return Err("use either `choices` or `choices_cmd`, not both".to_string());
+    }
+    let has_choices = !choices.is_empty() || raw.choices_cmd.is_some();
+    if raw.kind == ArgType::Enum && !has_choices {
+        return Err("type enum needs `choices` or `choices_cmd`".to_string());
+    }
+    if matches!(raw.kind, ArgType::Bool | ArgType::Flag) && has_choices {
+        return Err(format!("type {} can't have `choices`", raw.kind));
+    }
+    if (raw.on.is_some() || raw.off.is_some()) && raw.kind != ArgType::Flag {
+        return Err(format!("`on` and `off` need type flag, not {}", raw.kind));
+    }
+    if raw.kind == ArgType::Flag && raw.variadic {
+        return Err("type flag can't be variadic".to_string());
+    }
+    let short = match raw.short {
+        None => None,
+        Some(short) => {
+            let mut chars = short.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphabetic() => Some(c),
+                _ => return Err(format!("`short` must be a single letter, not `{short}`")),
+            }
+        }
+    };
+    if let Some(ref env) = raw.env
+        && (env.is_empty() || env.contains(['=', '\0']))
+    {
+        return Err(format!("`env` must be a variable name, not `{env}`"));
+    }
+    let min_count = raw.min_count.unwrap_or(0);
+    Ok(ArgDef {
+        default,
+        computed,
+        // A flag that isn't given is simply off
+        optional: raw.optional || (raw.variadic && min_count == 0) || raw.kind == ArgType::Flag,
+        kind: raw.kind,
+        help: raw.help,
+        min: raw.min,
+        max: raw.max,
+        must_exist: raw.must_exist,
+        choices,
+        choices_cmd: raw.choices_cmd,
+        variadic: raw.variadic,
+        min_count,
+        short,
+        on: raw.on,
+        off: raw.off,
+        env: raw.env,
+        forget: raw.remember == Some(false),
+    })
+}
+
+/// A default or choice as the string passed to the command.
+fn scalar(value: toml::Value) -> Result<String, String> {
+    match value {
+        toml::Value::String(s) => Ok(s),
+        toml::Value::Integer(i) => Ok(i.to_string()),
+        toml::Value::Float(f) => Ok(f.to_string()),
+        toml::Value::Boolean(b) => Ok(b.to_string()),
+        other => Err(format!(
+            "expected a string, number or boolean, found {}",
+            other.type_str()
+        )),
     }
-    Ok(args)
 }
 
 // ---------- TOML deserialization types ----------
@@ -36,16 +414,217 @@ where
 #[derive(Debug, Deserialize)]
 pub struct ConfigFile {
     #[serde(default)]
+    pub default: Option<String>,
+    /// Requirements shared by every task in this file.
+    #[serde(default)]
+    pub requires: Vec<Requirement>,
+    /// Directory (relative to this file) whose executables become tasks.
+    #[serde(default)]
+    pub scripts_dir: Option<PathBuf>,
+    /// Other tools whose tasks are exposed alongside the ones defined here.
+    #[serde(default)]
+    pub providers: Vec<Provider>,
+    /// Sets `strict_args` for every task in this file.
+    #[serde(default)]
+    pub strict_args: bool,
+    #[serde(default, deserialize_with = "deserialize_tasks")]
     pub tasks: IndexMap<String, TaskDef>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Accept nested tables (`[tasks.db.migrate]`) as an alternative to
+/// `[tasks."db:migrate"]`. A table with a `cmd` key is a task; a table whose
+/// values are all tables is a namespace. A task can be a namespace too:
+/// `[tasks.db.migrate]` next to `[tasks.db]` is `db:migrate`.
+fn deserialize_tasks<'de, D>(deserializer: D) -> Result<IndexMap<String, TaskDef>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = toml::Table::deserialize(deserializer)?;
+    let mut tasks = IndexMap::new();
+    flatten_tasks(raw, None, &mut tasks).map_err(D::Error::custom)?;
+    Ok(tasks)
+}
+
+fn flatten_tasks(
+    table: toml::Table,
+    namespace: Option<&str>,
+    tasks: &mut IndexMap<String, TaskDef>,
+) -> Result<(), String> {
+    for (key, value) in table {
+        let name = match namespace {
+            Some(ns) => format!("{ns}{}{key}", namespace::SEPARATOR),
+            None => key,
+        };
+        match value {
+            toml::Value::Table(t) if is_namespace(&t) => flatten_tasks(t, Some(&name), tasks)?,
+            toml::Value::Table(mut t) => {
+                let nested = split_nested(&mut t);
+                insert_task(toml::Value::Table(t), &name, tasks)?;
+                flatten_tasks(nested, Some(&name), tasks)?;
+            }
+            value => insert_task(value, &name, tasks)?,
+        }
+    }
+    Ok(())
+}
+
+fn insert_task(
+    value: toml::Value,
+    name: &str,
+    tasks: &mut IndexMap<String, TaskDef>,
+) -> Result<(), String> {
+    let def: TaskDef = value
+        .try_into()
+        .map_err(|e: toml::de::Error| format!("task `{name}`: {}", e.message()))?;
+    if tasks.contains_key(name) {
+        // Same wording as toml's own error so it maps to DuplicateTask
+        return Err(format!("duplicate key `{name}`"));
+    }
+    tasks.insert(name.to_string(), def);
+    Ok(())
+}
+
+/// Task keys that hold tables; any other table under a task
+/// (`[tasks.db.migrate]` next to `[tasks.db]`) is a task of its own.
+const TASK_TABLE_KEYS: &[&str] = &["args", "matrix"];
+
+fn is_nested_task(key: &str, value: &toml::Value) -> bool {
+    value.is_table() && !TASK_TABLE_KEYS.contains(&key)
+}
+
+/// Take the tables nested under a task out of it, to flatten as tasks in
+/// its namespace.
+fn split_nested(task: &mut toml::Table) -> toml::Table {
+    let keys: Vec<String> = task
+        .iter()
+        .filter(|(key, value)| is_nested_task(key, value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    keys.into_iter()
+        .filter_map(|key| task.remove(&key).map(|value| (key, value)))
+        .collect()
+}
+
+fn is_namespace(table: &toml::Table) -> bool {
+    !table.is_empty() && !table.contains_key("cmd") && table.values().all(toml::Value::is_table)
+}
+
+/// Flattening the `tasks` table loses where each task was written, so an
+/// error in one would point at the first `[tasks.*]` header. To report it
+/// at the right line, deserialize the tasks again one by one, straight from
+/// the file, and return the first error.
+fn locate_task_error(content: &str) -> Option<toml::de::Error> {
+    let doc: toml::Table = toml::from_str(content).ok()?;
+    let mut paths = Vec::new();
+    task_paths(
+        doc.get("tasks")?.as_table()?,
+        &mut vec!["tasks".to_string()],
+        &mut paths,
+    );
+    paths.iter().find_map(|path| {
+        TaskAt(path)
+            .deserialize(toml::Deserializer::new(content))
+            .err()
+    })
+}
+
+/// Key paths of the tasks in `table`, as `flatten_tasks` finds them.
+fn task_paths(table: &toml::Table, prefix: &mut Vec<String>, paths: &mut Vec<Vec<String>>) {
+    for (key, value) in table {
+        prefix.push(key.clone());
+        match value {
+            toml::Value::Table(t) if is_namespace(t) => task_paths(t, prefix, paths),
+            toml::Value::Table(t) => {
+                paths.push(prefix.clone());
+                let nested = t
+                    .iter()
+                    .filter(|(key, value)| is_nested_task(key, value))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                task_paths(&nested, prefix, paths);
+            }
+            _ => paths.push(prefix.clone()),
+        }
+        prefix.pop();
+    }
+}
+
+/// Deserializes only the task at a key path, skipping everything else.
+struct TaskAt<'a>(&'a [String]);
+
+impl<'de> DeserializeSeed<'de> for TaskAt<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        if self.0.is_empty() {
+            TaskDef::deserialize(deserializer).map(drop)
+        } else {
+            deserializer.deserialize_map(self)
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for TaskAt<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a table")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.0[0] {
+                map.next_value_seed(TaskAt(&self.0[1..]))?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct TaskDef {
     pub cmd: String,
     #[serde(default)]
     pub description: Option<String>,
     #[serde(default, deserialize_with = "deserialize_args")]
     pub args: IndexMap<String, ArgDef>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub deprecated: Option<String>,
+    #[serde(default)]
+    pub replaced_by: Option<String>,
+    #[serde(default)]
+    pub requires: Vec<Requirement>,
+    #[serde(default)]
+    pub preconditions: Vec<Precondition>,
+    #[serde(default)]
+    pub status: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_matrix")]
+    pub matrix: Matrix,
+    /// Append args given after `--` to `cmd` when it doesn't place them
+    /// itself with `$@` or `$MUU_ARGS`.
+    #[serde(default)]
+    pub passthrough: bool,
+    /// Parse args the strict way: all positional or all `--name=value`.
+    #[serde(default)]
+    pub strict_args: bool,
+    /// Executable run directly instead of `cmd`; set for tasks discovered
+    /// in a `scripts_dir`.
+    #[serde(skip)]
+    pub script: Option<PathBuf>,
+}
+
+/// A check run before the task; if `sh` fails, the task aborts with `msg`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Precondition {
+    pub sh: String,
+    #[serde(default)]
+    pub msg: Option<String>,
 }
 
 // ---------- Resolved types ----------
@@ -54,6 +633,10 @@ pub struct TaskDef {
 pub enum TaskSource {
     Local,
     Global,
+    Script,
+    Npm,
+    Make,
+    Cargo,
 }
 
 impl std::fmt::Display for TaskSource {
@@ -61,6 +644,10 @@ impl std::fmt::Display for TaskSource {
         match self {
             TaskSource::Local => write!(f, "local"),
             TaskSource::Global => write!(f, "global"),
+            TaskSource::Script => write!(f, "script"),
+            TaskSource::Npm => write!(f, "npm"),
+            TaskSource::Make => write!(f, "make"),
+            TaskSource::Cargo => write!(f, "cargo"),
         }
     }
 }
@@ -72,12 +659,77 @@ pub struct ResolvedTask {
     pub source: TaskSource,
 }
 
+impl ResolvedTask {
+    /// Whether `name` refers to this task, either by name or by alias.
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.def.aliases.iter().any(|a| a == name)
+    }
+
+    /// Task name followed by its aliases, e.g. `build (b, bld)`.
+    pub fn label(&self) -> String {
+        self.with_aliases(&self.name)
+    }
+
+    /// Like [`label`](Self::label) but without the namespace, for grouped views.
+    pub fn short_label(&self) -> String {
+        self.with_aliases(namespace::leaf(&self.name))
+    }
+
+    /// Whether the task passes a tag filter. Each filter is a comma-separated
+    /// list of tags that must all be present (AND); the task matches if any
+    /// filter does (OR). An empty filter list matches everything.
+    pub fn has_tags(&self, filters: &[String]) -> bool {
+        filters.is_empty()
+            || filters.iter().any(|f| {
+                f.split(',')
+                    .all(|tag| self.def.tags.iter().any(|t| t == tag.trim()))
+            })
+    }
+
+    /// A task is deprecated if it has a `deprecated` message or a `replaced_by` target.
+    pub fn is_deprecated(&self) -> bool {
+        self.def.deprecated.is_some() || self.def.replaced_by.is_some()
+    }
+
+    /// Deprecation notice, falling back to pointing at the replacement.
+    pub fn deprecation_message(&self) -> Option<String> {
+        match (&self.def.deprecated, &self.def.replaced_by) {
+            (Some(msg), _) => Some(msg.clone()),
+            (None, Some(next)) => Some(format!("use `{next}` instead")),
+            (None, None) => None,
+        }
+    }
+
+    /// Tags formatted for display, e.g. `#ci #frontend`.
+    pub fn tags_label(&self) -> String {
+        self.def
+            .tags
+            .iter()
+            .map(|t| format!("#{t}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn with_aliases(&self, name: &str) -> String {
+        if self.def.aliases.is_empty() {
+            name.to_string()
+        } else {
+            format!("{name} ({})", self.def.aliases.join(", "))
+        }
+    }
+}
+
+/// Find a task by name or alias.
+pub fn find_task<'a>(tasks: &'a [ResolvedTask], name: &str) -> Option<&'a ResolvedTask> {
+    tasks.iter().find(|t| t.matches(name))
+}
+
 // ---------- Parsing ----------
 
 pub fn parse_config(path: &Path) -> Result<ConfigFile, MuuError> {
     let content = std::fs::read_to_string(path).map_err(MuuError::Io)?;
-    toml::from_str::<ConfigFile>(&content).map_err(|e| {
-        let msg = e.to_string();
+    let mut cfg = toml::from_str::<ConfigFile>(&content).map_err(|e| {
+        let msg = locate_task_error(&content).unwrap_or(e).to_string();
         if let Some(name) = extract_duplicate_key(&msg) {
             MuuError::DuplicateTask {
                 name,
@@ -89,7 +741,37 @@ pub fn parse_config(path: &Path) -> Result<ConfigFile, MuuError> {
                 reason: msg,
             }
         }
-    })
+    })?;
+
+    // File-level requirements are checked before task-level ones
+    if !cfg.requires.is_empty() {
+        for def in cfg.tasks.values_mut() {
+            def.requires.splice(0..0, cfg.requires.iter().cloned());
+        }
+    }
+    if cfg.strict_args {
+        for def in cfg.tasks.values_mut() {
+            def.strict_args = true;
+        }
+    }
+    Ok(cfg)
+}
+
+/// Reject aliases that clash with a task name or another alias in the same file.
+fn check_aliases(cfg: &ConfigFile, path: &Path) -> Result<(), MuuError> {
+    let mut seen: Vec<&str> = cfg.tasks.keys().map(String::as_str).collect();
+    for def in cfg.tasks.values() {
+        for alias in &def.aliases {
+            if seen.contains(&alias.as_str()) {
+                return Err(MuuError::DuplicateTask {
+                    name: alias.clone(),
+                    path: path.to_path_buf(),
+                });
+            }
+            seen.push(alias);
+        }
+    }
+    Ok(())
 }
 
 fn extract_duplicate_key(msg: &str) -> Option<String> {
@@ -114,17 +796,34 @@ pub fn find_local_config(start: &Path) -> Option<PathBuf> {
     }
 }
 
+/// The project directory: where the nearest `muu.toml` is.
+pub fn project_root(start: &Path) -> Option<PathBuf> {
+    find_local_config(start).and_then(|path| path.parent().map(Path::to_path_buf))
+}
+
 pub fn global_config_path() -> Option<PathBuf> {
     dirs::home_dir().map(|d| d.join(".config").join("muu").join("config.toml"))
 }
 
 // ---------- Loading & merging ----------
 
+/// Merged result of the local and global config files.
+#[derive(Debug)]
+pub struct Loaded {
+    pub tasks: Vec<ResolvedTask>,
+    /// Task run by a bare `muu`; local takes precedence over global.
+    pub default: Option<String>,
+}
+
 pub fn load_tasks(
     start_dir: &Path,
     local_only: bool,
     global_only: bool,
 ) -> Result<Vec<ResolvedTask>, MuuError> {
+    load(start_dir, local_only, global_only).map(|loaded| loaded.tasks)
+}
+
+pub fn load(start_dir: &Path, local_only: bool, global_only: bool) -> Result<Loaded, MuuError> {
     let local_path = if !global_only {
         find_local_config(start_dir)
     } else {
@@ -141,10 +840,14 @@ pub fn load_tasks(
     }
 
     let mut tasks: IndexMap<String, ResolvedTask> = IndexMap::new();
+    let mut default = None;
 
     // Global first (will be overridden by local)
     if let Some(ref gp) = global_path {
         let cfg = parse_config(gp)?;
+        check_aliases(&cfg, gp)?;
+        default = cfg.default.clone();
+        let scripts = script_tasks(&cfg, gp)?;
         for (name, def) in cfg.tasks {
             if tasks.contains_key(&name) {
                 return Err(MuuError::DuplicateTask {
@@ -161,11 +864,19 @@ pub fn load_tasks(
                 },
             );
         }
+        add_scripts(&mut tasks, scripts);
+        add_provided(&mut tasks, &cfg.providers, gp);
     }
 
     // Local overrides global
     if let Some(ref lp) = local_path {
         let cfg = parse_config(lp)?;
+        check_aliases(&cfg, lp)?;
+        shadow_global(&mut tasks, &cfg);
+        if cfg.default.is_some() {
+            default = cfg.default.clone();
+        }
+        let scripts = script_tasks(&cfg, lp)?;
         let mut seen_local: IndexMap<String, ()> = IndexMap::new();
         for (name, def) in cfg.tasks {
             if seen_local.contains_key(&name) {
@@ -184,9 +895,82 @@ pub fn load_tasks(
                 },
             );
         }
+        add_scripts(&mut tasks, scripts);
+        add_provided(&mut tasks, &cfg.providers, lp);
     }
 
-    Ok(tasks.into_values().collect())
+    Ok(Loaded {
+        tasks: tasks.into_values().collect(),
+        default,
+    })
+}
+
+/// Tasks discovered in the file's `scripts_dir`, minus any whose name is
+/// already taken by a task or alias defined in the file itself.
+fn script_tasks(cfg: &ConfigFile, path: &Path) -> Result<Vec<(String, TaskDef)>, MuuError> {
+    let Some(ref dir) = cfg.scripts_dir else {
+        return Ok(Vec::new());
+    };
+    let base = path.parent().unwrap_or(Path::new("."));
+    let taken = |name: &String| {
+        cfg.tasks.contains_key(name) || cfg.tasks.values().any(|d| d.aliases.contains(name))
+    };
+    Ok(scripts::discover(&base.join(dir))?
+        .into_iter()
+        .filter(|(name, _)| !taken(name))
+        .map(|(name, mut def)| {
+            def.requires = cfg.requires.clone();
+            def.strict_args = cfg.strict_args;
+            (name, def)
+        })
+        .collect())
+}
+
+fn add_scripts(tasks: &mut IndexMap<String, ResolvedTask>, scripts: Vec<(String, TaskDef)>) {
+    for (name, def) in scripts {
+        tasks.insert(
+            name.clone(),
+            ResolvedTask {
+                name,
+                def,
+                source: TaskSource::Script,
+            },
+        );
+    }
+}
+
+/// Add tasks from the file's providers. Tasks already loaded, whether
+/// defined explicitly or by an earlier provider, win name conflicts.
+fn add_provided(tasks: &mut IndexMap<String, ResolvedTask>, providers: &[Provider], path: &Path) {
+    let root = path.parent().unwrap_or(Path::new("."));
+    for provider in providers {
+        for (name, def) in provider.discover(root) {
+            if tasks.values().any(|t| t.matches(&name)) {
+                continue;
+            }
+            tasks.insert(
+                name.clone(),
+                ResolvedTask {
+                    name,
+                    def,
+                    source: provider.source(),
+                },
+            );
+        }
+    }
+}
+
+/// Apply local-over-global precedence to aliases: a global task whose name is
+/// claimed by a local alias is hidden, and global aliases that collide with a
+/// local name or alias are dropped.
+fn shadow_global(tasks: &mut IndexMap<String, ResolvedTask>, local: &ConfigFile) {
+    let local_aliases: Vec<&String> = local.tasks.values().flat_map(|d| &d.aliases).collect();
+    tasks.retain(|name, _| !local_aliases.contains(&name));
+    for task in tasks.values_mut() {
+        task.def
+            .aliases
+            .retain(|a| !local.tasks.contains_key(a) && !local_aliases.contains(&a));
+    }
 }
 
 #[cfg(test)]
@@ -221,10 +1005,7 @@ args = { dir = ".", bucket = "" }
         let cfg = parse_config(&path).unwrap();
         assert_eq!(cfg.tasks.len(), 2);
         assert_eq!(cfg.tasks["hello"].cmd, "echo hello");
-        assert_eq!(
-            cfg.tasks["hello"].description.as_deref(),
-            Some("Say hello")
-        );
+        assert_eq!(cfg.tasks["hello"].description.as_deref(), Some("Say hello"));
         assert!(cfg.tasks["hello"].args.is_empty());
 
         let deploy = &cfg.tasks["deploy"];
@@ -350,6 +1131,565 @@ args = { name = "?" }
         assert!(greet.args["name"].optional);
     }
 
+    #[test]
+    fn parse_table_args() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+[tasks.retry]
+cmd = "retry $count $env $out"
+args.count = { type = "int", default = 3, help = "Number of retries", min = 1, max = 10 }
+args.env = { type = "enum", choices = ["dev", "prod"], default = "dev" }
+args.out = { type = "path", must_exist = true, optional = true, remember = false }
+args.name = "world"
+args.replicas = { type = "int", choices = [1, 3, 5], default = 1 }
+"#,
+        );
+        let cfg = parse_config(&path).unwrap();
+        let args = &cfg.tasks["retry"].args;
+        let keys: Vec<&String> = args.keys().collect();
+        assert_eq!(keys, vec!["count", "env", "out", "name", "replicas"]);
+        assert_eq!(
+            args["count"],
+            ArgDef {
+                default: "3".to_string(),
+                kind: ArgType::Int,
+                help: Some("Number of retries".to_string()),
+                min: Some(1.0),
+                max: Some(10.0),
+                ..Default::default()
+            }
+        );
+        assert_eq!(args["env"].choices, vec!["dev", "prod"]);
+        assert!(args["out"].must_exist && args["out"].optional);
+        assert!(args["out"].forget && !args["env"].forget);
+        assert_eq!(args["name"], shorthand_arg("world".to_string()));
+        assert_eq!(args["replicas"].choices, vec!["1", "3", "5"]);
+    }
+
+    #[test]
+    fn parse_variadic_args() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+[tasks.lint]
+cmd = "eslint $files"
+args = { fix = "false", files = "..." }
+
+[tasks.copy]
+cmd = "cp $files $dest"
+args.dest = ""
+args.files = { variadic = true, min_count = 1, type = "path" }
+"#,
+        );
+        let cfg = parse_config(&path).unwrap();
+        let files = &cfg.tasks["lint"].args["files"];
+        assert!(files.variadic && files.optional);
+        assert_eq!(files.min_count, 0);
+        let files = &cfg.tasks["copy"].args["files"];
+        assert!(files.variadic && !files.optional);
+        assert_eq!(files.min_count, 1);
+
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            "[tasks.t]\ncmd = \"true\"\nargs = { files = \"...\", dest = \"\" }\n",
+        );
+        let err = parse_config(&path).unwrap_err().to_string();
+        assert!(
+            err.contains("arg `files`: only the last arg can be variadic"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn parse_short_aliases_and_strict_args() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+strict_args = true
+
+[tasks.deploy]
+cmd = "deploy $bucket $dir"
+args.bucket = { short = "b" }
+args.dir = { short = "d", default = "." }
+"#,
+        );
+        let cfg = parse_config(&path).unwrap();
+        let deploy = &cfg.tasks["deploy"];
+        assert_eq!(deploy.args["bucket"].short, Some('b'));
+        assert!(deploy.strict_args);
+
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            "[tasks.t]\ncmd = \"true\"\nargs.a = { short = \"x\" }\nargs.b = { short = \"x\" }\n",
+        );
+        let err = parse_config(&path).unwrap_err().to_string();
+        assert!(
+            err.contains("arg `b`: short alias `-x` is already taken"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn parse_flag_args() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+[tasks.build]
+cmd = "cargo build $release $verbose"
+args.release = { type = "flag", on = "--release", off = "", short = "r" }
+args.verbose = { type = "flag", default = true }
+"#,
+        );
+        let cfg = parse_config(&path).unwrap();
+        let release = &cfg.tasks["build"].args["release"];
+        assert_eq!(release.kind, ArgType::Flag);
+        assert!(release.optional);
+        assert_eq!(release.default, "false");
+        assert_eq!(release.flag_text(true), "--release");
+        assert_eq!(release.flag_text(false), "");
+        let verbose = &cfg.tasks["build"].args["verbose"];
+        assert_eq!(verbose.default, "true");
+        assert_eq!(verbose.flag_text(true), "true");
+    }
+
+    #[test]
+    fn parse_computed_defaults() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+[tasks.deploy]
+cmd = "deploy $bucket $branch"
+args.env = "dev"
+args.bucket = "{{ env }}-bucket"
+args.branch = { default = { sh = "git rev-parse --abbrev-ref HEAD" } }
+args.format = "{{.Names}}"
+args.escaped = "{{{{ env }}"
+"#,
+        );
+        let cfg = parse_config(&path).unwrap();
+        let args = &cfg.tasks["deploy"].args;
+        assert_eq!(args["env"].computed, None);
+        assert_eq!(
+            args["bucket"].computed,
+            Some(Computed::Template("{{ env }}-bucket".to_string()))
+        );
+        assert_eq!(args["bucket"].default, "");
+        assert!(!args["bucket"].is_required());
+        assert_eq!(
+            args["branch"].computed,
+            Some(Computed::Sh("git rev-parse --abbrev-ref HEAD".to_string()))
+        );
+        // Placeholders that name no arg or env variable are plain text
+        assert_eq!(args["format"].default, "{{.Names}}");
+        assert_eq!(args["escaped"].default, "{{ env }}");
+        assert_eq!(args["escaped"].computed, None);
+    }
+
+    #[test]
+    fn parse_table_args_rejects_inconsistent_definitions() {
+        let dir = TempDir::new().unwrap();
+        for (args, message) in [
+            (r#"{ type = "enum" }"#, "type enum needs `choices`"),
+            (r#"{ choices = ["a"], choices_cmd = "ls" }"#, "not both"),
+            (r#"{ type = "string", min = 1 }"#, "need type int or float"),
+            (
+                r#"{ type = "int", min = 5, max = 1 }"#,
+                "greater than `max`",
+            ),
+            (r#"{ must_exist = true }"#, "needs type path"),
+            (r#"{ min_count = 1 }"#, "needs `variadic = true`"),
+            (r#"{ short = "bb" }"#, "single letter"),
+            (r#"{ on = "--x" }"#, "need type flag"),
+            (r#"{ env = "A=B" }"#, "must be a variable name"),
+            (r#"{ default = "{{ nope }}-x" }"#, "unknown arg `nope`"),
+            (
+                r#"{ default = { sh = "echo {{ nope }}" } }"#,
+                "unknown arg `nope`",
+            ),
+            (r#"{ default = { cmd = "ls" } }"#, "takes only `sh"),
+            (
+                r#"{ type = "flag", default = { sh = "true" } }"#,
+                "computed default",
+            ),
+            (
+                r#"{ type = "flag", default = "yes" }"#,
+                "must be true or false",
+            ),
+            (
+                r#"{ variadic = true, default = "a" }"#,
+                "can't have `default`",
+            ),
+            (
+                r#"{ type = "bool", choices = ["a"] }"#,
+                "can't have `choices`",
+            ),
+            (r#"{ choices = [[1]] }"#, "found array"),
+            (r#"{ type = "date" }"#, "unknown variant `date`"),
+            (r#"{ dfault = "x" }"#, "unknown field `dfault`"),
+        ] {
+            let path = write_file(
+                dir.path(),
+                "muu.toml",
+                &format!("[tasks.t]\ncmd = \"true\"\nargs.x = {args}\n"),
+            );
+            let err = parse_config(&path).unwrap_err().to_string();
+            assert!(err.contains("arg `x`"), "{err}");
+            assert!(err.contains(message), "{args}: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_matrix_values() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            "[tasks.t]\ncmd = \"true\"\nmatrix = { n = [1, 2], debug = [true], env = [\"dev\"] }\n",
+        );
+        let cfg = parse_config(&path).unwrap();
+        assert_eq!(cfg.tasks["t"].matrix["n"], vec!["1", "2"]);
+        assert_eq!(cfg.tasks["t"].matrix["debug"], vec!["true"]);
+
+        for (matrix, message) in [
+            ("{ env = [] }", "needs at least one value"),
+            ("{ env = [[1]] }", "found array"),
+        ] {
+            let path = write_file(
+                dir.path(),
+                "muu.toml",
+                &format!("[tasks.t]\ncmd = \"true\"\nmatrix = {matrix}\n"),
+            );
+            let err = parse_config(&path).unwrap_err().to_string();
+            assert!(err.contains("matrix `env`"), "{err}");
+            assert!(err.contains(message), "{matrix}: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_aliases() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+[tasks.build]
+cmd = "cargo build"
+aliases = ["b", "bld"]
+"#,
+        );
+        let cfg = parse_config(&path).unwrap();
+        assert_eq!(cfg.tasks["build"].aliases, vec!["b", "bld"]);
+        assert!(check_aliases(&cfg, &path).is_ok());
+    }
+
+    #[test]
+    fn alias_clashes_with_task_name() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+[tasks.build]
+cmd = "cargo build"
+aliases = ["b"]
+
+[tasks.b]
+cmd = "echo b"
+"#,
+        );
+        let cfg = parse_config(&path).unwrap();
+        let err = check_aliases(&cfg, &path).unwrap_err();
+        assert!(matches!(err, MuuError::DuplicateTask { name, .. } if name == "b"));
+    }
+
+    #[test]
+    fn alias_clashes_with_alias() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+[tasks.build]
+cmd = "cargo build"
+aliases = ["b"]
+
+[tasks.bench]
+cmd = "cargo bench"
+aliases = ["b"]
+"#,
+        );
+        let cfg = parse_config(&path).unwrap();
+        let err = check_aliases(&cfg, &path).unwrap_err();
+        assert!(matches!(err, MuuError::DuplicateTask { name, .. } if name == "b"));
+    }
+
+    #[test]
+    fn local_alias_shadows_global() {
+        let dir = TempDir::new().unwrap();
+        let global = parse_config(&write_file(
+            dir.path(),
+            "config.toml",
+            r#"
+[tasks.b]
+cmd = "echo global b"
+
+[tasks.test]
+cmd = "echo global test"
+aliases = ["t", "bld"]
+"#,
+        ))
+        .unwrap();
+        let local = parse_config(&write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+[tasks.build]
+cmd = "cargo build"
+aliases = ["b", "bld"]
+"#,
+        ))
+        .unwrap();
+
+        let mut tasks: IndexMap<String, ResolvedTask> = global
+            .tasks
+            .into_iter()
+            .map(|(name, def)| {
+                let task = ResolvedTask {
+                    name: name.clone(),
+                    def,
+                    source: TaskSource::Global,
+                };
+                (name, task)
+            })
+            .collect();
+        shadow_global(&mut tasks, &local);
+
+        assert!(!tasks.contains_key("b"));
+        assert_eq!(tasks["test"].def.aliases, vec!["t"]);
+    }
+
+    #[test]
+    fn find_task_by_alias() {
+        let tasks = vec![ResolvedTask {
+            name: "build".to_string(),
+            def: TaskDef {
+                cmd: "cargo build".to_string(),
+                aliases: vec!["b".to_string()],
+                ..Default::default()
+            },
+            source: TaskSource::Local,
+        }];
+        assert_eq!(find_task(&tasks, "b").unwrap().name, "build");
+        assert_eq!(find_task(&tasks, "build").unwrap().name, "build");
+        assert!(find_task(&tasks, "bld").is_none());
+        assert_eq!(tasks[0].label(), "build (b)");
+    }
+
+    #[test]
+    fn parse_nested_namespaces() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+[tasks.build]
+cmd = "cargo build"
+
+[tasks.db.migrate]
+cmd = "migrate up"
+args = { steps = "1" }
+
+[tasks."db:seed"]
+cmd = "seed"
+
+[tasks.cloud.aws.deploy]
+cmd = "deploy"
+"#,
+        );
+        let cfg = parse_config(&path).unwrap();
+        let keys: Vec<&String> = cfg.tasks.keys().collect();
+        assert_eq!(
+            keys,
+            vec!["build", "db:migrate", "db:seed", "cloud:aws:deploy"]
+        );
+        assert_eq!(cfg.tasks["db:migrate"].args["steps"].default, "1");
+    }
+
+    #[test]
+    fn parse_tasks_nested_under_a_task() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+[tasks.db]
+cmd = "db shell"
+args = { name = "app" }
+matrix = { env = ["dev", "prod"] }
+
+[tasks.db.migrate]
+cmd = "migrate up"
+
+[tasks.db.seed.demo]
+cmd = "seed demo"
+"#,
+        );
+        let cfg = parse_config(&path).unwrap();
+        let keys: Vec<&String> = cfg.tasks.keys().collect();
+        assert_eq!(keys, vec!["db", "db:migrate", "db:seed:demo"]);
+        assert_eq!(cfg.tasks["db"].args["name"].default, "app");
+        assert_eq!(cfg.tasks["db:migrate"].cmd, "migrate up");
+    }
+
+    #[test]
+    fn nested_and_quoted_duplicate() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+[tasks."db:migrate"]
+cmd = "a"
+
+[tasks.db.migrate]
+cmd = "b"
+"#,
+        );
+        let err = parse_config(&path).unwrap_err();
+        assert!(matches!(err, MuuError::DuplicateTask { name, .. } if name == "db:migrate"));
+    }
+
+    #[test]
+    fn task_errors_point_at_the_task() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"[tasks.a]
+cmd = "true"
+
+[tasks.db.migrate]
+cmd = "true"
+
+[tasks.db.seed]
+description = "no cmd"
+"#,
+        );
+        let err = parse_config(&path).unwrap_err();
+        let MuuError::ConfigParse { reason, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert!(reason.contains("line 7"), "{reason}");
+        assert!(reason.contains("missing field `cmd`"), "{reason}");
+    }
+
+    #[test]
+    fn empty_task_table_is_error() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(dir.path(), "muu.toml", "[tasks.hello]\n");
+        let err = parse_config(&path).unwrap_err();
+        assert!(matches!(err, MuuError::ConfigParse { .. }));
+    }
+
+    #[test]
+    fn tag_filters() {
+        let task = ResolvedTask {
+            name: "lint".to_string(),
+            def: TaskDef {
+                cmd: "eslint".to_string(),
+                tags: vec!["ci".to_string(), "frontend".to_string()],
+                ..Default::default()
+            },
+            source: TaskSource::Local,
+        };
+        let filters = |f: &[&str]| f.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(task.has_tags(&[]));
+        assert!(task.has_tags(&filters(&["ci"])));
+        assert!(task.has_tags(&filters(&["ci,frontend"])));
+        assert!(!task.has_tags(&filters(&["ci,backend"])));
+        assert!(task.has_tags(&filters(&["backend", "frontend"])));
+        assert!(!task.has_tags(&filters(&["backend"])));
+    }
+
+    #[test]
+    fn parse_default_task() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+default = "dev"
+
+[tasks.dev]
+cmd = "vite"
+"#,
+        );
+        let loaded = load(dir.path(), true, false).unwrap();
+        assert_eq!(loaded.default.as_deref(), Some("dev"));
+        assert_eq!(parse_config(&path).unwrap().tasks.len(), 1);
+    }
+
+    #[test]
+    fn deprecation_message_falls_back_to_replacement() {
+        let mut task = ResolvedTask {
+            name: "migrate".to_string(),
+            def: TaskDef {
+                cmd: "true".to_string(),
+                replaced_by: Some("db:migrate".to_string()),
+                ..Default::default()
+            },
+            source: TaskSource::Local,
+        };
+        assert!(task.is_deprecated());
+        assert_eq!(
+            task.deprecation_message().as_deref(),
+            Some("use `db:migrate` instead")
+        );
+
+        task.def.deprecated = Some("gone in v2".to_string());
+        assert_eq!(task.deprecation_message().as_deref(), Some("gone in v2"));
+    }
+
+    #[test]
+    fn parse_requires_merges_file_level() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            dir.path(),
+            "muu.toml",
+            r#"
+requires = ["git"]
+
+[tasks.build]
+cmd = "npm run build"
+requires = ["node >= 18", { tool = "kubectl", version = "^1.28", probe = "kubectl version --client" }]
+"#,
+        );
+        let cfg = parse_config(&path).unwrap();
+        let reqs: Vec<String> = cfg.tasks["build"]
+            .requires
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+        assert_eq!(reqs, vec!["git", "node >= 18", "kubectl ^1.28"]);
+        assert_eq!(
+            cfg.tasks["build"].requires[2].probe.as_deref(),
+            Some("kubectl version --client")
+        );
+    }
+
     #[test]
     fn args_order_preserved() {
         let dir = TempDir::new().unwrap();
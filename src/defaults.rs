@@ -0,0 +1,310 @@
+use std::time::Duration;
+
+use indexmap::IndexMap;
+
+use crate::config::{ArgDef, Computed};
+use crate::error::MuuError;
+use crate::runner;
+use crate::task;
+
+/// How long a `default = { sh = "..." }` command may run.
+const SH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A piece of a default template.
+#[derive(Debug, PartialEq)]
+enum Part<'a> {
+    Text(&'a str),
+    /// `{{ name }}`: another arg's value.
+    Arg(&'a str),
+    /// `{{ $NAME }}`: an environment variable, empty if unset.
+    Env(&'a str),
+}
+
+/// Split a default into text and placeholders. Only `{{ $VAR }}` and
+/// `{{ name }}` naming an arg are placeholders; anything else, like Go's
+/// `{{.Names}}`, stays text. `{{{{` is a literal `{{`.
+fn parse<'a>(text: &'a str, is_arg: &dyn Fn(&str) -> bool) -> Vec<Part<'a>> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        parts.push(Part::Text(&rest[..start]));
+        if let Some(tail) = rest[start..].strip_prefix("{{{{") {
+            parts.push(Part::Text("{{"));
+            rest = tail;
+            continue;
+        }
+        let inner = &rest[start + 2..];
+        let placeholder = inner.find("}}").and_then(|end| {
+            let name = inner[..end].trim();
+            let part = match name.strip_prefix('$') {
+                Some(var) if is_var(var) => Part::Env(var),
+                None if is_arg(name) => Part::Arg(name),
+                _ => return None,
+            };
+            Some((part, &inner[end + 2..]))
+        });
+        match placeholder {
+            Some((part, tail)) => {
+                parts.push(part);
+                rest = tail;
+            }
+            None => {
+                parts.push(Part::Text("{{"));
+                rest = inner;
+            }
+        }
+    }
+    parts.push(Part::Text(rest));
+    parts
+}
+
+fn is_var(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A default without placeholders as the plain value it stands for (with
+/// `{{{{` unescaped), or `None` if it has to be computed.
+pub fn literal(text: &str, is_arg: &dyn Fn(&str) -> bool) -> Option<String> {
+    parse(text, is_arg)
+        .into_iter()
+        .map(|part| match part {
+            Part::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The first `{{ name }}` in a default that looks like an arg reference but
+/// names no arg, likely a typo. Other text in braces, like `{{.Names}}`, is
+/// left alone.
+pub fn unknown_reference<'a>(text: &'a str, is_arg: &dyn Fn(&str) -> bool) -> Option<&'a str> {
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        if let Some(tail) = rest[start..].strip_prefix("{{{{") {
+            rest = tail;
+            continue;
+        }
+        rest = &rest[start + 2..];
+        let name = rest.split_once("}}").map_or("", |(name, _)| name.trim());
+        if is_var(name) && !is_arg(name) {
+            return Some(name);
+        }
+    }
+    None
+}
+
+/// The args a computed default refers to.
+pub fn references<'a>(text: &'a str, defined: &IndexMap<String, ArgDef>) -> Vec<&'a str> {
+    parse(text, &|name| defined.contains_key(name))
+        .into_iter()
+        .filter_map(|part| match part {
+            Part::Arg(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Work out a computed default from the args resolved so far. `None` when
+/// it refers to an arg that isn't resolved yet.
+pub fn evaluate(
+    name: &str,
+    computed: &Computed,
+    defined: &IndexMap<String, ArgDef>,
+    resolved: &IndexMap<String, String>,
+) -> Result<Option<String>, MuuError> {
+    let sh = matches!(computed, Computed::Sh(_));
+    let mut out = String::new();
+    for part in parse(computed.text(), &|arg| defined.contains_key(arg)) {
+        let value = match part {
+            Part::Text(text) => {
+                out.push_str(text);
+                continue;
+            }
+            Part::Arg(arg) => match resolved.get(arg) {
+                Some(value) => value.clone(),
+                None => return Ok(None),
+            },
+            Part::Env(var) => std::env::var(var).unwrap_or_default(),
+        };
+        out.push_str(&if sh { task::shell_quote(&value) } else { value });
+    }
+    if !sh {
+        return Ok(Some(out));
+    }
+    match runner::output(&out, SH_TIMEOUT) {
+        Some(output) => Ok(Some(output.trim_end().to_string())),
+        None => Err(MuuError::DefaultCommandFailed {
+            name: name.to_string(),
+            cmd: out,
+        }),
+    }
+}
+
+/// Compute the defaults of the `pending` args, each after the args it
+/// refers to, and add them to `resolved`, which ends up in definition
+/// order. Defaults that refer to each other in a loop are an error.
+pub fn compute(
+    defined: &IndexMap<String, ArgDef>,
+    pending: &[&str],
+    resolved: &mut IndexMap<String, String>,
+) -> Result<(), MuuError> {
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let mut chain = Vec::new();
+    for name in pending {
+        visit(name, defined, pending, resolved, &mut chain)?;
+    }
+    let mut ordered: IndexMap<String, String> = defined
+        .keys()
+        .filter_map(|name| resolved.swap_remove(name).map(|v| (name.clone(), v)))
+        .collect();
+    ordered.extend(resolved.drain(..));
+    *resolved = ordered;
+    Ok(())
+}
+
+fn visit<'a>(
+    name: &'a str,
+    defined: &'a IndexMap<String, ArgDef>,
+    pending: &[&str],
+    resolved: &mut IndexMap<String, String>,
+    chain: &mut Vec<&'a str>,
+) -> Result<(), MuuError> {
+    if resolved.contains_key(name) || !pending.contains(&name) {
+        return Ok(());
+    }
+    if let Some(start) = chain.iter().position(|n| *n == name) {
+        let mut cycle = chain[start..].to_vec();
+        cycle.push(name);
+        return Err(MuuError::DefaultCycle {
+            cycle: cycle.join(" -> "),
+        });
+    }
+    let Some((key, arg)) = defined.get_key_value(name) else {
+        return Ok(());
+    };
+    let Some(ref computed) = arg.computed else {
+        return Ok(());
+    };
+
+    chain.push(key);
+    for dep in references(computed.text(), defined) {
+        visit(dep, defined, pending, resolved, chain)?;
+    }
+    chain.pop();
+
+    let value = evaluate(name, computed, defined, resolved)?.unwrap_or_default();
+    let value = task::check_resolved(name, arg, value)?;
+    resolved.insert(key.clone(), value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn computed(args: &[(&str, Computed)]) -> IndexMap<String, ArgDef> {
+        args.iter()
+            .map(|(name, computed)| {
+                (
+                    name.to_string(),
+                    ArgDef {
+                        computed: Some(computed.clone()),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_templates() {
+        let is_arg = |name: &str| name == "env";
+        assert_eq!(
+            parse("{{ env }}-{{$USER}}!", &is_arg),
+            vec![
+                Part::Text(""),
+                Part::Arg("env"),
+                Part::Text("-"),
+                Part::Env("USER"),
+                Part::Text("!"),
+            ]
+        );
+        assert_eq!(literal("{{ env }}", &is_arg), None);
+        assert_eq!(literal("{{ $HOME }}", &is_arg), None);
+
+        // Anything that isn't a placeholder stays as written
+        for text in ["{{.Names}}", "{{ other }}", "{{ open", "{{ $ }}", "a }} b"] {
+            assert_eq!(literal(text, &is_arg).as_deref(), Some(text));
+        }
+        // though a bare name that isn't an arg is most likely a typo
+        assert_eq!(unknown_reference("{{ evn }}-bucket", &is_arg), Some("evn"));
+        for text in [
+            "{{ env }}-{{$USER}}",
+            "{{.Names}}",
+            "{{ json . }}",
+            "{{{{ evn }}",
+            "{{ evn",
+        ] {
+            assert_eq!(unknown_reference(text, &is_arg), None, "{text}");
+        }
+        assert_eq!(
+            literal("{{{{ env }}", &is_arg).as_deref(),
+            Some("{{ env }}")
+        );
+    }
+
+    #[test]
+    fn compute_in_dependency_order() {
+        let mut defined = computed(&[
+            (
+                "bucket",
+                Computed::Template("{{ env }}-{{ region }}".to_string()),
+            ),
+            ("region", Computed::Sh("echo {{ env }}-1".to_string())),
+        ]);
+        defined.insert("env".to_string(), ArgDef::default());
+
+        let mut resolved = IndexMap::from([("env".to_string(), "dev".to_string())]);
+        compute(&defined, &["bucket", "region"], &mut resolved).unwrap();
+        let pairs: Vec<(&str, &str)> = resolved
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![("bucket", "dev-dev-1"), ("region", "dev-1"), ("env", "dev")]
+        );
+    }
+
+    #[test]
+    fn compute_detects_cycles() {
+        let defined = computed(&[
+            ("a", Computed::Template("{{ b }}".to_string())),
+            ("b", Computed::Template("{{ a }}".to_string())),
+        ]);
+        let err = compute(&defined, &["a", "b"], &mut IndexMap::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "argument defaults refer to each other: a -> b -> a"
+        );
+
+        // An explicit value breaks the loop
+        let mut resolved = IndexMap::from([("b".to_string(), "x".to_string())]);
+        compute(&defined, &["a"], &mut resolved).unwrap();
+        assert_eq!(resolved["a"], "x");
+    }
+
+    #[test]
+    fn failing_command_is_an_error() {
+        let defined = computed(&[("a", Computed::Sh("exit 1".to_string()))]);
+        let err = compute(&defined, &["a"], &mut IndexMap::new()).unwrap_err();
+        assert!(
+            matches!(err, MuuError::DefaultCommandFailed { .. }),
+            "{err}"
+        );
+    }
+}
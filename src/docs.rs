@@ -0,0 +1,519 @@
+use std::path::Path;
+
+use crate::config::{ArgDef, ArgType, ResolvedTask, TaskSource};
+use crate::namespace::{self, Row};
+
+/// Output formats for `muu docs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Markdown
+    Md,
+    /// man page (roff)
+    Man,
+    /// Standalone HTML page
+    Html,
+}
+
+/// Markdown output between these markers replaces only that part of an
+/// existing file, so a README can embed the generated task reference.
+pub const START_MARKER: &str = "<!-- muu:docs -->";
+pub const END_MARKER: &str = "<!-- /muu:docs -->";
+
+const GENERATED: &str = "Generated by `muu docs`; edit muu.toml instead.";
+
+/// Render every task, grouped by source and then by namespace. Script paths
+/// are shown relative to `base` so the output doesn't depend on the machine.
+pub fn render(format: Format, tasks: &[ResolvedTask], base: &Path) -> String {
+    let groups = groups(tasks);
+    match format {
+        Format::Md => markdown(&groups, base),
+        Format::Man => man(&groups, base),
+        Format::Html => html(&groups, base),
+    }
+}
+
+/// Plain-text help for one task, as printed by `muu show` and `--help`.
+pub fn show(task: &ResolvedTask, base: &Path) -> String {
+    let mut out = format!("Usage: {}\n", usage(task));
+    if let Some(ref desc) = task.def.description {
+        out.push_str(&format!("\n{desc}\n"));
+    }
+    let details = details(task);
+    if !details.is_empty() {
+        out.push('\n');
+        for (label, value) in details {
+            out.push_str(&format!("{label}: {value}\n"));
+        }
+    }
+    if !task.def.args.is_empty() {
+        out.push_str("\nArguments:\n");
+        let width = task.def.args.keys().map(String::len).max().unwrap_or(0);
+        for (name, arg) in &task.def.args {
+            out.push_str(&format!("  {name:<width$}  {}\n", arg_note(arg)));
+        }
+    }
+    if let Some(body) = body(task, base) {
+        out.push_str("\nRuns:\n");
+        for line in body.lines() {
+            out.push_str(&format!("  {line}\n"));
+        }
+    }
+    out
+}
+
+/// Replace the text between the markers in `existing` with `generated`.
+/// Returns `None` when `existing` has no markers.
+/// Whether `content` is a whole file written by `muu docs`, which can be
+/// replaced as a whole.
+pub fn is_generated(content: &str) -> bool {
+    content.contains(GENERATED)
+}
+
+pub fn splice(existing: &str, generated: &str) -> Option<String> {
+    let start = existing.find(START_MARKER)? + START_MARKER.len();
+    let end = start + existing[start..].find(END_MARKER)?;
+    Some(format!(
+        "{}\n{generated}{}",
+        &existing[..start],
+        &existing[end..]
+    ))
+}
+
+// ---------- Shared model ----------
+
+/// Tasks of one source, in the order sources first appear.
+fn groups(tasks: &[ResolvedTask]) -> Vec<(TaskSource, Vec<ResolvedTask>)> {
+    let mut groups: Vec<(TaskSource, Vec<ResolvedTask>)> = Vec::new();
+    for task in tasks {
+        match groups.iter_mut().find(|(source, _)| *source == task.source) {
+            Some((_, group)) => group.push(task.clone()),
+            None => groups.push((task.source, vec![task.clone()])),
+        }
+    }
+    groups
+}
+
+fn title(source: TaskSource) -> &'static str {
+    match source {
+        TaskSource::Local => "Local tasks",
+        TaskSource::Global => "Global tasks",
+        TaskSource::Script => "Scripts",
+        TaskSource::Npm => "npm scripts",
+        TaskSource::Make => "Make targets",
+        TaskSource::Cargo => "Cargo aliases",
+    }
+}
+
+/// e.g. `muu deploy [dir] <bucket> [tags...]`: required args in angle
+/// brackets, variadic ones with `...`.
+fn usage(task: &ResolvedTask) -> String {
+    let mut parts = vec!["muu".to_string(), task.name.clone()];
+    parts.extend(task.def.args.iter().map(|(name, arg)| {
+        let dots = if arg.variadic { "..." } else { "" };
+        if arg.kind == ArgType::Flag {
+            format!("[--{name}]")
+        } else if arg.is_required() {
+            format!("<{name}{dots}>")
+        } else {
+            format!("[{name}{dots}]")
+        }
+    }));
+    if task.def.passthrough {
+        parts.push("[-- args...]".to_string());
+    }
+    parts.join(" ")
+}
+
+/// Help text, type and default or marker for the argument table,
+/// e.g. `Number of retries (int, 1 to 10, default: 3)`.
+fn arg_note(arg: &ArgDef) -> String {
+    let mut parts = Vec::new();
+    if let Some(short) = arg.short {
+        parts.push(format!("-{short}"));
+    }
+    match arg.kind {
+        _ if arg.choices_cmd.is_some() => {}
+        _ if !arg.choices.is_empty() => parts.push(format!("one of {}", arg.choices.join(", "))),
+        ArgType::String | ArgType::Enum => {}
+        ArgType::Path if arg.must_exist => parts.push("existing path".to_string()),
+        kind => parts.push(kind.to_string()),
+    }
+    if let Some(ref cmd) = arg.choices_cmd {
+        parts.push(format!("one of the lines printed by `{cmd}`"));
+    }
+    match (arg.min, arg.max) {
+        (Some(min), Some(max)) => parts.push(format!("{min} to {max}")),
+        (Some(min), None) => parts.push(format!("at least {min}")),
+        (None, Some(max)) => parts.push(format!("at most {max}")),
+        (None, None) => {}
+    }
+    if let Some(ref env) = arg.env {
+        parts.push(format!("env: ${env}"));
+    }
+    parts.push(if arg.kind == ArgType::Flag {
+        match (arg.on.as_deref(), arg.off.as_deref()) {
+            (None, None) => format!("default: {}", arg.default),
+            (on, Some("")) => format!("`{}` when set", on.unwrap_or("true")),
+            (on, off) => format!(
+                "`{}` when set, `{}` otherwise",
+                on.unwrap_or("true"),
+                off.unwrap_or("false")
+            ),
+        }
+    } else if arg.variadic {
+        match arg.min_count {
+            0 => "any number of values".to_string(),
+            n => format!("at least {n} value(s)"),
+        }
+    } else if arg.is_required() {
+        "required".to_string()
+    } else if let Some(ref computed) = arg.computed {
+        format!("default: {computed}")
+    } else if arg.default.is_empty() {
+        "optional".to_string()
+    } else {
+        format!("default: {}", arg.default)
+    });
+    let note = parts.join(", ");
+    match arg.help {
+        Some(ref help) => format!("{help} ({note})"),
+        None => note,
+    }
+}
+
+/// The command body, if it is worth showing. Provider tasks only delegate
+/// to their tool, so their generated `cmd` is left out.
+fn body(task: &ResolvedTask, base: &Path) -> Option<String> {
+    if let Some(ref script) = task.def.script {
+        let path = script.strip_prefix(base).unwrap_or(script);
+        return Some(path.display().to_string());
+    }
+    match task.source {
+        TaskSource::Local | TaskSource::Global => Some(task.def.cmd.trim_end().to_string()),
+        _ => None,
+    }
+}
+
+/// Aliases, tags and deprecation as short `label: value` pairs.
+fn details(task: &ResolvedTask) -> Vec<(&'static str, String)> {
+    let mut details = Vec::new();
+    if !task.def.aliases.is_empty() {
+        details.push(("Aliases", task.def.aliases.join(", ")));
+    }
+    if !task.def.tags.is_empty() {
+        details.push(("Tags", task.def.tags.join(", ")));
+    }
+    if let Some(msg) = task.deprecation_message() {
+        details.push(("Deprecated", msg));
+    }
+    details
+}
+
+// ---------- Markdown ----------
+
+fn markdown(groups: &[(TaskSource, Vec<ResolvedTask>)], base: &Path) -> String {
+    let mut out = format!("<!-- {GENERATED} -->\n\n# Tasks\n");
+    for (source, tasks) in groups {
+        out.push_str(&format!("\n## {}\n", title(*source)));
+        for row in namespace::tree(tasks) {
+            match row {
+                Row::Namespace { name, depth } => {
+                    out.push_str(&format!("\n{} `{name}`\n", heading(depth)));
+                }
+                Row::Task { task, depth } => md_task(&mut out, task, depth, base),
+            }
+        }
+    }
+    out
+}
+
+fn heading(depth: usize) -> String {
+    "#".repeat((depth + 3).min(6))
+}
+
+fn md_task(out: &mut String, task: &ResolvedTask, depth: usize, base: &Path) {
+    out.push_str(&format!("\n{} `{}`\n\n", heading(depth), task.name));
+    if let Some(ref desc) = task.def.description {
+        out.push_str(&format!("{desc}\n\n"));
+    }
+    let details = details(task);
+    for (label, value) in &details {
+        out.push_str(&format!("- **{label}:** {value}\n"));
+    }
+    if !details.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(&format!("```sh\n{}\n```\n", usage(task)));
+    if !task.def.args.is_empty() {
+        out.push_str("\n| Argument | |\n| --- | --- |\n");
+        for (name, arg) in &task.def.args {
+            let note = arg_note(arg).replace('|', "\\|");
+            out.push_str(&format!("| `{name}` | {note} |\n"));
+        }
+    }
+    if let Some(body) = body(task, base) {
+        out.push_str(&format!("\nRuns:\n\n```sh\n{body}\n```\n"));
+    }
+}
+
+// ---------- man ----------
+
+fn man(groups: &[(TaskSource, Vec<ResolvedTask>)], base: &Path) -> String {
+    let mut out = format!(".\\\" {GENERATED}\n");
+    out.push_str(".TH MUU-TASKS 7 \"\" muu \"Project tasks\"\n");
+    out.push_str(".SH NAME\nmuu\\-tasks \\- tasks defined for this project\n");
+    for (source, tasks) in groups {
+        out.push_str(&format!(".SH {}\n", title(*source).to_uppercase()));
+        for row in namespace::tree(tasks) {
+            let Row::Task { task, .. } = row else {
+                continue;
+            };
+            out.push_str(&format!(".SS {}\n", roff(&task.name)));
+            if let Some(ref desc) = task.def.description {
+                out.push_str(&format!("{}\n", roff(desc)));
+            }
+            for (label, value) in details(task) {
+                out.push_str(&format!(".PP\n.B {label}:\n{}\n", roff(&value)));
+            }
+            out.push_str(&format!(".PP\n.B {}\n", roff(&usage(task))));
+            for (name, arg) in &task.def.args {
+                out.push_str(&format!(
+                    ".TP\n.I {}\n{}\n",
+                    roff(name),
+                    roff(&arg_note(arg))
+                ));
+            }
+            if let Some(body) = body(task, base) {
+                out.push_str(".PP\n.EX\n");
+                for line in body.lines() {
+                    out.push_str(&format!("{}\n", roff(line)));
+                }
+                out.push_str(".EE\n");
+            }
+        }
+    }
+    out
+}
+
+/// Escape text for roff: backslashes, dashes, and leading control characters.
+fn roff(text: &str) -> String {
+    let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+    if escaped.starts_with(['.', '\'']) {
+        format!("\\&{escaped}")
+    } else {
+        escaped
+    }
+}
+
+// ---------- HTML ----------
+
+fn html(groups: &[(TaskSource, Vec<ResolvedTask>)], base: &Path) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<!-- {GENERATED} -->\n<html lang=\"en\">\n<head>\n\
+         <meta charset=\"utf-8\">\n<title>Tasks</title>\n</head>\n<body>\n<h1>Tasks</h1>\n"
+    );
+    for (source, tasks) in groups {
+        out.push_str(&format!("<h2>{}</h2>\n", escape(title(*source))));
+        for row in namespace::tree(tasks) {
+            match row {
+                Row::Namespace { name, depth } => {
+                    let level = (depth + 3).min(6);
+                    out.push_str(&format!(
+                        "<h{level}><code>{}</code></h{level}>\n",
+                        escape(name)
+                    ));
+                }
+                Row::Task { task, depth } => html_task(&mut out, task, depth, base),
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn html_task(out: &mut String, task: &ResolvedTask, depth: usize, base: &Path) {
+    let level = (depth + 3).min(6);
+    out.push_str(&format!(
+        "<h{level} id=\"task-{}\"><code>{}</code></h{level}>\n",
+        escape(&task.name.replace(namespace::SEPARATOR, "-")),
+        escape(&task.name)
+    ));
+    if let Some(ref desc) = task.def.description {
+        out.push_str(&format!("<p>{}</p>\n", escape(desc)));
+    }
+    let details = details(task);
+    if !details.is_empty() {
+        out.push_str("<ul>\n");
+        for (label, value) in details {
+            out.push_str(&format!(
+                "<li><strong>{label}:</strong> {}</li>\n",
+                escape(&value)
+            ));
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str(&format!(
+        "<pre><code>{}</code></pre>\n",
+        escape(&usage(task))
+    ));
+    if !task.def.args.is_empty() {
+        out.push_str("<table>\n<tr><th>Argument</th><th></th></tr>\n");
+        for (name, arg) in &task.def.args {
+            out.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td></tr>\n",
+                escape(name),
+                escape(&arg_note(arg))
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+    if let Some(body) = body(task, base) {
+        out.push_str(&format!(
+            "<p>Runs:</p>\n<pre><code>{}</code></pre>\n",
+            escape(&body)
+        ));
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::config::TaskDef;
+
+    fn task(name: &str, args: &[(&str, &str, bool)]) -> ResolvedTask {
+        ResolvedTask {
+            name: name.to_string(),
+            def: TaskDef {
+                cmd: format!("echo {name}"),
+                args: args
+                    .iter()
+                    .map(|(n, d, optional)| {
+                        (
+                            n.to_string(),
+                            ArgDef {
+                                default: d.to_string(),
+                                optional: *optional,
+                                ..Default::default()
+                            },
+                        )
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            source: TaskSource::Local,
+        }
+    }
+
+    #[test]
+    fn usage_marks_required_args() {
+        let t = task(
+            "deploy",
+            &[
+                ("dir", ".", false),
+                ("bucket", "", false),
+                ("tag", "", true),
+            ],
+        );
+        assert_eq!(usage(&t), "muu deploy [dir] <bucket> [tag]");
+
+        let mut t = task("lint", &[]);
+        t.def.args.insert(
+            "files".to_string(),
+            ArgDef {
+                variadic: true,
+                min_count: 1,
+                ..Default::default()
+            },
+        );
+        assert_eq!(usage(&t), "muu lint <files...>");
+        assert_eq!(arg_note(&t.def.args["files"]), "at least 1 value(s)");
+    }
+
+    #[test]
+    fn markdown_groups_namespaces() {
+        let tasks = vec![task("db:migrate", &[]), task("build", &[])];
+        let md = render(Format::Md, &tasks, Path::new("/"));
+        let headings: Vec<&str> = md.lines().filter(|l| l.starts_with('#')).collect();
+        assert_eq!(
+            headings,
+            vec![
+                "# Tasks",
+                "## Local tasks",
+                "### `db`",
+                "#### `db:migrate`",
+                "### `build`"
+            ]
+        );
+    }
+
+    #[test]
+    fn arg_note_describes_typed_args() {
+        let arg = ArgDef {
+            default: "3".to_string(),
+            kind: ArgType::Int,
+            help: Some("Number of retries".to_string()),
+            min: Some(1.0),
+            max: Some(10.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            arg_note(&arg),
+            "Number of retries (int, 1 to 10, default: 3)"
+        );
+
+        let flag = ArgDef {
+            default: "false".to_string(),
+            kind: ArgType::Flag,
+            optional: true,
+            on: Some("--release".to_string()),
+            off: Some(String::new()),
+            ..Default::default()
+        };
+        assert_eq!(arg_note(&flag), "flag, `--release` when set");
+    }
+
+    #[test]
+    fn show_lists_args_and_command() {
+        let mut t = task("deploy", &[("dir", ".", false), ("bucket", "", false)]);
+        t.def.description = Some("Deploy the site".to_string());
+        t.def.args["bucket"].env = Some("DEPLOY_BUCKET".to_string());
+        assert_eq!(
+            show(&t, Path::new("/")),
+            "Usage: muu deploy [dir] <bucket>\n\
+             \n\
+             Deploy the site\n\
+             \n\
+             Arguments:\n  \
+             dir     default: .\n  \
+             bucket  env: $DEPLOY_BUCKET, required\n\
+             \n\
+             Runs:\n  \
+             echo deploy\n"
+        );
+    }
+
+    #[test]
+    fn splice_between_markers() {
+        let readme = format!("# App\n\n{START_MARKER}\nold\n{END_MARKER}\n\nMore\n");
+        assert_eq!(
+            splice(&readme, "new\n").unwrap(),
+            format!("# App\n\n{START_MARKER}\nnew\n{END_MARKER}\n\nMore\n")
+        );
+        assert!(splice("no markers", "new").is_none());
+    }
+
+    #[test]
+    fn roff_escaping() {
+        assert_eq!(roff(".hidden"), "\\&.hidden");
+        assert_eq!(roff("a-b \\n"), "a\\-b \\en");
+    }
+}
@@ -22,15 +22,117 @@ pub enum MuuError {
     #[error("unknown argument '{name}'")]
     UnknownArg { name: String },
 
+    #[error("invalid value '{value}' for argument '{name}': expected {expected}")]
+    InvalidArgValue {
+        name: String,
+        value: String,
+        expected: String,
+    },
+
+    #[error("invalid value '{value}' for argument '{name}', expected one of: {}", .choices.join(", "))]
+    InvalidChoice {
+        name: String,
+        value: String,
+        choices: Vec<String>,
+    },
+
+    #[error("argument '{name}' must be {bound}, got {value}")]
+    ArgOutOfRange {
+        name: String,
+        value: String,
+        bound: String,
+    },
+
+    #[error("path '{path}' for argument '{name}' does not exist")]
+    ArgPathNotFound { name: String, path: PathBuf },
+
+    #[error("argument '{name}' needs a value")]
+    MissingArgValue { name: String },
+
+    #[error("argument '{name}' given more than once")]
+    DuplicateArg { name: String },
+
+    #[error("too many arguments: expected at most {expected}, got {got}")]
+    TooManyArgs { expected: usize, got: usize },
+
+    #[error("argument '{name}' needs at least {min} value(s), got {got}")]
+    TooFewValues {
+        name: String,
+        min: usize,
+        got: usize,
+    },
+
+    #[error(
+        "task '{task}' does not take arguments after `--`; set `passthrough = true` or use `$@` in its cmd"
+    )]
+    PassthroughNotAccepted { task: String },
+
+    #[error("default of argument '{name}' failed: `{cmd}`")]
+    DefaultCommandFailed { name: String, cmd: String },
+
+    #[error("argument defaults refer to each other: {cycle}")]
+    DefaultCycle { cycle: String },
+
     #[error("cannot mix positional and named arguments")]
     MixedArgStyles,
 
     #[error("failed to parse {path}: {reason}")]
     ConfigParse { path: PathBuf, reason: String },
 
+    #[error("scripts directory {path} not found")]
+    ScriptsDirNotFound { path: PathBuf },
+
     #[error("no tasks defined")]
     NoTasksDefined,
 
+    #[error("no tasks match tag filter '{filter}'")]
+    NoTaggedTasks { filter: String },
+
+    #[error("'muu run' requires at least one --tag")]
+    MissingTagFilter,
+
+    #[error(
+        "unmet requirements:\n{}",
+        .failures.iter().map(|f| format!("  - {f}")).collect::<Vec<_>>().join("\n")
+    )]
+    RequirementsNotMet { failures: Vec<String> },
+
+    #[error("invalid --matrix '{spec}', expected name=value1,value2")]
+    InvalidMatrix { spec: String },
+
+    #[error("argument '{name}' is set by the matrix; use --matrix {name}=... to choose its values")]
+    MatrixArgGiven { name: String },
+
+    #[error("{path} not found")]
+    ImportSourceNotFound { path: PathBuf },
+
+    #[error("{path} is out of date; run `muu docs` to regenerate it")]
+    StaleDocs { path: PathBuf },
+
+    #[error(
+        "{path} exists and wasn't generated by muu; add `<!-- muu:docs -->` and `<!-- /muu:docs -->` markers where the docs should go"
+    )]
+    DocsTargetExists { path: PathBuf },
+
+    #[error("{message}")]
+    PreconditionFailed { message: String },
+
     #[error("{0}")]
     Io(#[from] std::io::Error),
 }
+
+/// Exit code for a bare `muu` without a TTY: the task list is printed
+/// instead of a prompt, and nothing is run. Not 2, which clap uses for
+/// usage errors.
+pub const EXIT_NOT_INTERACTIVE: i32 = 4;
+
+impl MuuError {
+    /// Process exit code for this error. Most errors exit with 1; failed
+    /// preconditions get their own code so scripts can tell them apart.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            MuuError::PreconditionFailed { .. } => 3,
+            _ => 1,
+        }
+    }
+}
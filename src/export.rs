@@ -0,0 +1,1090 @@
+use indexmap::IndexMap;
+use serde_json::{Map, Value, json};
+
+use crate::config::{ArgDef, ArgType, ResolvedTask};
+use crate::error::MuuError;
+use crate::task::{self, shell_quote};
+
+/// Formats `muu export` can generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Makefile with args as make variables
+    Makefile,
+    /// justfile with args as recipe parameters
+    Justfile,
+    /// .vscode/tasks.json with args as input variables
+    Vscode,
+    /// GitHub Actions workflow with args as workflow_dispatch inputs
+    GithubActions,
+}
+
+pub fn render(format: Format, tasks: &[ResolvedTask]) -> String {
+    match format {
+        Format::Makefile => makefile(tasks),
+        Format::Justfile => justfile(tasks),
+        Format::Vscode => vscode(tasks),
+        Format::GithubActions => github_actions(tasks),
+    }
+}
+
+fn header(command: &str) -> String {
+    format!("# Generated by `muu {command}`; edit muu.toml instead.\n")
+}
+
+// ---------- Shared helpers ----------
+
+/// The task's command with `$arg` placeholders; scripts get their args as
+/// positional parameters, as they do when muu runs them.
+fn command(task: &ResolvedTask) -> String {
+    match task.def.script {
+        Some(ref path) => std::iter::once(shell_quote(&path.display().to_string()))
+            .chain(task.def.args.keys().map(|a| format!("\"${a}\"")))
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => task.def.cmd.clone(),
+    }
+}
+
+/// Rewrite a command for another tool: `$arg` placeholders go through `arg`
+/// (longest name first, like `task::expand_command`) and all other text
+/// through `text`, so it can be escaped.
+fn substitute(
+    cmd: &str,
+    args: &IndexMap<String, ArgDef>,
+    arg: impl Fn(&str) -> String,
+    text: impl Fn(&str) -> String,
+) -> String {
+    let mut names: Vec<&str> = args.keys().map(String::as_str).collect();
+    names.sort_by_key(|n| std::cmp::Reverse(n.len()));
+
+    let mut out = String::new();
+    let mut plain = String::new();
+    let mut rest = cmd;
+    while let Some(i) = rest.find('$') {
+        plain.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+        match names.iter().find(|n| after.starts_with(**n)) {
+            Some(name) => {
+                out.push_str(&text(&plain));
+                plain.clear();
+                out.push_str(&arg(name));
+                rest = &after[name.len()..];
+            }
+            None => {
+                plain.push('$');
+                rest = after;
+            }
+        }
+    }
+    plain.push_str(rest);
+    out.push_str(&text(&plain));
+    out
+}
+
+/// Shell line that fails like `MuuError::MissingRequiredArg` when `value`
+/// expands to an empty string.
+fn require_line(name: &str, value: &str) -> String {
+    format!("test -n \"{value}\" || {{ echo \"missing required argument '{name}'\" >&2; exit 1; }}")
+}
+
+/// Computed defaults aren't evaluated on export, so such args start out
+/// empty; this says so in a comment, or in the description where there are
+/// no comments.
+fn computed_note(name: &str, arg: &ArgDef) -> Option<String> {
+    arg.computed
+        .as_ref()
+        .map(|computed| format!("TODO: `{name}` defaults to {computed} in muu.toml"))
+}
+
+/// A double-quoted string, valid in YAML and just as well as JSON.
+fn quoted(s: &str) -> String {
+    Value::String(s.to_string()).to_string()
+}
+
+/// Input/job identifier: task and arg names joined, without namespace colons.
+fn ident(parts: &[&str]) -> String {
+    parts.join("-").replace(':', "-")
+}
+
+// ---------- Makefile ----------
+
+/// Arg names are kept lowercase so they can't collide with environment
+/// variables such as PATH, which make would otherwise pick up.
+fn make_var(arg: &str) -> String {
+    arg.replace('-', "_")
+}
+
+fn make_target(name: &str) -> String {
+    name.replace(':', "\\:")
+}
+
+/// Text inside a make function call, where `,` would split the arguments.
+fn make_text(text: &str) -> String {
+    text.replace('$', "$$").replace(',', "$(comma)")
+}
+
+/// A make variable as the task sees it: flags become their on/off text.
+fn make_value(arg: &ArgDef, var: &str) -> String {
+    if arg.kind != ArgType::Flag {
+        return format!("$({var})");
+    }
+    format!(
+        "$(if $(filter true yes on 1,$({var})),{},{})",
+        make_text(&arg.flag_text(true)),
+        make_text(&arg.flag_text(false))
+    )
+}
+
+fn makefile(tasks: &[ResolvedTask]) -> String {
+    let mut out = header("export makefile");
+    // muu runs each cmd as a single zsh script with `set -e`
+    out.push_str("\nSHELL := zsh\n.SHELLFLAGS := -ec\n.ONESHELL:\n");
+    let flag_texts = tasks
+        .iter()
+        .flat_map(|t| t.def.args.values())
+        .filter(|a| a.kind == ArgType::Flag)
+        .flat_map(|a| [a.flag_text(true), a.flag_text(false)]);
+    if flag_texts.into_iter().any(|t| t.contains(',')) {
+        out.push_str("comma := ,\n");
+    }
+    let targets: Vec<String> = tasks.iter().map(|t| make_target(&t.name)).collect();
+    out.push_str(&format!("\n.PHONY: {}\n", targets.join(" ")));
+
+    for (task, target) in tasks.iter().zip(&targets) {
+        out.push('\n');
+        if let Some(ref desc) = task.def.description {
+            out.push_str(&format!("## {desc}\n"));
+        }
+        for (name, arg) in &task.def.args {
+            let var = make_var(name);
+            if let Some(note) = computed_note(name, arg) {
+                out.push_str(&format!("# {note}\n"));
+            }
+            if arg.default.is_empty() {
+                out.push_str(&format!("{target}: {var} =\n"));
+            } else {
+                let default = arg.default.replace('$', "$$");
+                out.push_str(&format!("{target}: {var} = {default}\n"));
+            }
+        }
+        out.push_str(&format!("{target}:\n"));
+        for (name, arg) in &task.def.args {
+            if arg.is_required() {
+                let var = make_var(name);
+                out.push_str(&format!(
+                    "\t$(if $({var}),,$(error missing required argument '{name}'))\n"
+                ));
+            }
+        }
+        let cmd = substitute(
+            &command(task),
+            &task.def.args,
+            |a| make_value(&task.def.args[a], &make_var(a)),
+            |t| t.replace('$', "$$"),
+        );
+        for line in cmd.trim_end().lines() {
+            out.push_str(&format!("\t{line}\n"));
+        }
+    }
+    out
+}
+
+// ---------- justfile ----------
+
+fn just_recipe(name: &str) -> String {
+    name.replace(':', "-")
+}
+
+/// A recipe parameter as the task sees it: flags become their on/off text.
+fn just_value(arg: &ArgDef, name: &str) -> String {
+    if arg.kind != ArgType::Flag {
+        return format!("{{{{{name}}}}}");
+    }
+    format!(
+        "{{{{ if {name} =~ \"(?i)^(true|yes|on|1)$\" {{ {} }} else {{ {} }} }}}}",
+        quoted(&arg.flag_text(true)),
+        quoted(&arg.flag_text(false))
+    )
+}
+
+fn justfile(tasks: &[ResolvedTask]) -> String {
+    let mut out = header("export justfile");
+    out.push_str("\nset shell := [\"zsh\", \"-c\"]\n");
+
+    for task in tasks {
+        let recipe = just_recipe(&task.name);
+        out.push('\n');
+        // Before the description, which just shows as the recipe's doc
+        for (name, arg) in &task.def.args {
+            if let Some(note) = computed_note(name, arg) {
+                out.push_str(&format!("# {note}\n"));
+            }
+        }
+        if let Some(ref desc) = task.def.description {
+            out.push_str(&format!("# {desc}\n"));
+        }
+
+        // just requires parameters with defaults to come last, so a required
+        // arg after one with a default gets an empty default and a check
+        let mut params = vec![recipe.clone()];
+        let mut checks = Vec::new();
+        let mut seen_default = false;
+        for (name, arg) in &task.def.args {
+            if arg.is_required() && !seen_default {
+                params.push(name.clone());
+                continue;
+            }
+            seen_default = true;
+            params.push(format!("{name}={}", quoted(&arg.default)));
+            if arg.is_required() {
+                checks.push(require_line(name, &format!("{{{{{name}}}}}")));
+            }
+        }
+
+        let cmd = substitute(
+            &command(task),
+            &task.def.args,
+            |a| just_value(&task.def.args[a], a),
+            |t| t.replace("{{", "{{{{"),
+        );
+        let mut lines = checks;
+        lines.extend(cmd.trim_end().lines().map(str::to_string));
+        out.push_str(&format!("{}:\n", params.join(" ")));
+        if lines.len() > 1 {
+            // A shebang recipe runs as one script, like muu does
+            out.push_str("    #!/usr/bin/env zsh\n    set -e\n");
+        }
+        for line in &lines {
+            if line.is_empty() {
+                out.push('\n');
+            } else {
+                out.push_str(&format!("    {line}\n"));
+            }
+        }
+        for alias in &task.def.aliases {
+            out.push_str(&format!("\nalias {} := {recipe}\n", just_recipe(alias)));
+        }
+    }
+    out
+}
+
+// ---------- VS Code ----------
+
+fn vscode(tasks: &[ResolvedTask]) -> String {
+    let mut entries = Vec::new();
+    let mut inputs = Vec::new();
+    for task in tasks {
+        let input_id = |arg: &str| ident(&[&task.name, arg]);
+        let cmd = substitute(
+            &command(task),
+            &task.def.args,
+            |a| format!("${{input:{}}}", input_id(a)),
+            str::to_string,
+        );
+
+        let mut entry = Map::new();
+        entry.insert("label".into(), json!(task.name));
+        if let Some(ref desc) = task.def.description {
+            entry.insert("detail".into(), json!(desc));
+        }
+        entry.insert("type".into(), json!("process"));
+        entry.insert("command".into(), json!("zsh"));
+        entry.insert(
+            "args".into(),
+            json!(["-c", format!("set -e\n{}", cmd.trim_end())]),
+        );
+        entry.insert("problemMatcher".into(), json!([]));
+        entries.push(Value::Object(entry));
+
+        for (name, arg) in &task.def.args {
+            let mut input = Map::new();
+            input.insert("id".into(), json!(input_id(name)));
+            if arg.kind == ArgType::Flag {
+                // The picked value is the text the flag expands to
+                let option =
+                    |set: bool| json!({ "label": set.to_string(), "value": arg.flag_text(set) });
+                input.insert("type".into(), json!("pickString"));
+                input.insert("options".into(), json!([option(false), option(true)]));
+            } else {
+                input.insert("type".into(), json!("promptString"));
+            }
+            // JSON has no comments, so the note goes in the description
+            let description = match computed_note(name, arg) {
+                Some(note) => format!("{} ({note})", arg_description(task, name, arg)),
+                None => arg_description(task, name, arg),
+            };
+            input.insert("description".into(), json!(description));
+            if arg.kind == ArgType::Flag {
+                input.insert(
+                    "default".into(),
+                    json!(arg.flag_text(arg.default == "true")),
+                );
+            } else if !arg.default.is_empty() {
+                input.insert("default".into(), json!(arg.default));
+            }
+            inputs.push(Value::Object(input));
+        }
+    }
+
+    let mut doc = Map::new();
+    doc.insert("version".into(), json!("2.0.0"));
+    doc.insert("tasks".into(), Value::Array(entries));
+    if !inputs.is_empty() {
+        doc.insert("inputs".into(), Value::Array(inputs));
+    }
+    let mut out = serde_json::to_string_pretty(&Value::Object(doc)).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// e.g. `deploy: bucket (required)`.
+fn arg_description(task: &ResolvedTask, name: &str, arg: &ArgDef) -> String {
+    let marker = if arg.is_required() {
+        " (required)"
+    } else if arg.optional && arg.default.is_empty() {
+        " (optional)"
+    } else {
+        ""
+    };
+    format!("{}: {name}{marker}", task.name)
+}
+
+// ---------- GitHub Actions ----------
+
+fn github_actions(tasks: &[ResolvedTask]) -> String {
+    let mut out = header("export github-actions");
+    out.push_str("name: muu\n\non:\n  workflow_dispatch:\n    inputs:\n");
+    out.push_str("      task:\n        description: Task to run\n        required: true\n        type: choice\n        options:\n");
+    for task in tasks {
+        out.push_str(&format!("          - {}\n", quoted(&task.name)));
+    }
+    for task in tasks {
+        for (name, arg) in &task.def.args {
+            if let Some(note) = computed_note(name, arg) {
+                out.push_str(&format!("      # {note}\n"));
+            }
+            out.push_str(&format!("      {}:\n", ident(&[&task.name, name])));
+            out.push_str(&format!(
+                "        description: {}\n",
+                quoted(&arg_description(task, name, arg))
+            ));
+            // Inputs are shared by all jobs, so none can be required here
+            if arg.kind == ArgType::Flag {
+                out.push_str("        type: boolean\n");
+                out.push_str(&format!("        default: {}\n", arg.default));
+            } else if !arg.default.is_empty() {
+                out.push_str(&format!("        default: {}\n", quoted(&arg.default)));
+            }
+        }
+    }
+
+    out.push_str("\njobs:\n");
+    for (i, task) in tasks.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let input = |arg: &str| {
+            let input = format!("inputs.{}", ident(&[&task.name, arg]));
+            match task.def.args.get(arg) {
+                Some(def) if def.kind == ArgType::Flag => {
+                    format!("${{{{ {} }}}}", actions_flag(def, &input))
+                }
+                _ => format!("${{{{ {input} }}}}"),
+            }
+        };
+        out.push_str(&format!("  {}:\n", ident(&[&task.name])));
+        out.push_str(&format!("    name: {}\n", quoted(&task.name)));
+        out.push_str(&format!(
+            "    if: inputs.task == '{}'\n",
+            task.name.replace('\'', "''")
+        ));
+        out.push_str("    runs-on: ubuntu-latest\n    steps:\n      - uses: actions/checkout@v4\n");
+        let step = task.def.description.as_deref().unwrap_or(&task.name);
+        out.push_str(&format!("      - name: {}\n", quoted(step)));
+        // The default shell on Actions runners is `bash -e`
+        out.push_str("        run: |\n");
+
+        let mut lines = Vec::new();
+        for (name, arg) in &task.def.args {
+            if arg.is_required() {
+                lines.push(require_line(name, &input(name)));
+            }
+        }
+        let cmd = substitute(&command(task), &task.def.args, input, str::to_string);
+        lines.extend(cmd.trim_end().lines().map(str::to_string));
+        for line in &lines {
+            if line.is_empty() {
+                out.push('\n');
+            } else {
+                out.push_str(&format!("          {line}\n"));
+            }
+        }
+    }
+    out
+}
+
+/// Expression for a boolean input's on/off text. `a && b || c` gives `c`
+/// whenever `b` is empty, so an empty `on` text needs the check negated.
+fn actions_flag(arg: &ArgDef, input: &str) -> String {
+    let literal = |text: &str| format!("'{}'", text.replace('\'', "''"));
+    let (on, off) = (arg.flag_text(true), arg.flag_text(false));
+    match (on.is_empty(), off.is_empty()) {
+        (false, _) => format!("{input} && {} || {}", literal(&on), literal(&off)),
+        (true, false) => format!("!{input} && {} || ''", literal(&off)),
+        (true, true) => "''".to_string(),
+    }
+}
+
+// ---------- Standalone script ----------
+
+/// Shell that runs the task's command in a standalone script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Shell {
+    /// zsh, as muu itself uses
+    Zsh,
+    /// POSIX sh, for environments without zsh
+    Sh,
+}
+
+impl Shell {
+    fn program(self) -> &'static str {
+        match self {
+            Shell::Zsh => "zsh",
+            Shell::Sh => "sh",
+        }
+    }
+}
+
+/// Shell functions shared by every generated script. `expand` mirrors
+/// `task::expand_command`, replacing placeholders one name at a time.
+const SCRIPT_HELPERS: &str = r#"die() {
+  printf '\033[31mError: %s\033[0m\n' "$1" >&2
+  exit "${2:-1}"
+}
+
+# Replace every occurrence of $1 in $expanded with $2.
+replace() {
+  rest=$expanded
+  expanded=
+  while :; do
+    case $rest in
+      *"$1"*)
+        expanded=$expanded${rest%%"$1"*}$2
+        rest=${rest#*"$1"}
+        ;;
+      *) break ;;
+    esac
+  done
+  expanded=$expanded$rest
+}
+"#;
+
+/// Shell functions for scripts of tasks with args. `quote` mirrors
+/// `task::shell_quote`, and `is_number` tells values like `-5` apart from
+/// short aliases.
+const SCRIPT_ARG_HELPERS: &str = r#"
+quote() {
+  case $1 in
+    ''|*[!A-Za-z0-9_./:=@%+,-]*) printf "'%s'" "$(printf '%s' "$1" | sed "s/'/'\\\\''/g")" ;;
+    *) printf '%s' "$1" ;;
+  esac
+}
+
+is_number() {
+  printf '%s\n' "$1" | grep -Eq '^[+-]?([0-9]+\.?[0-9]*|\.[0-9]+)([eE][+-]?[0-9]+)?$'
+}
+"#;
+
+/// A POSIX sh script that parses args like `task::resolve_args` (or
+/// `resolve_args_strict` with `strict_args`), runs the task's status
+/// checks, requirements and preconditions, and then its command, so it
+/// behaves like `muu <task>` without muu installed.
+pub fn script(task: &ResolvedTask, shell: Shell) -> String {
+    let def = &task.def;
+    let sh = shell.program();
+    let names: Vec<&String> = def.args.keys().collect();
+    let is_flag = |name: &str| def.args[name].kind == ArgType::Flag;
+    // Flags are only ever named, so they take no positional slot
+    let slots: Vec<&String> = names.iter().copied().filter(|n| !is_flag(n)).collect();
+    let var = |name: &str| format!("arg_{}", def.args.get_index_of(name).unwrap_or(0) + 1);
+    let variadic = slots
+        .iter()
+        .copied()
+        .find(|n| def.args[n.as_str()].variadic);
+
+    let mut out = String::from("#!/bin/sh\n");
+    match def.description {
+        Some(ref desc) => out.push_str(&format!("# {}: {desc}\n", task.name)),
+        None => out.push_str(&format!("# {}\n", task.name)),
+    }
+    out.push_str(&header(&format!("export-script {}", task.name)));
+    if !names.is_empty() {
+        let positional: Vec<String> = slots.iter().map(|n| format!("[{n}]")).collect();
+        let named: Vec<String> = names
+            .iter()
+            .map(|n| match is_flag(n) {
+                true => format!("[--{n}]"),
+                false => format!("[--{n}=VALUE]"),
+            })
+            .collect();
+        out.push_str(&format!(
+            "#\n# Usage: {} {}\n#    or: {} {}\n",
+            task.name,
+            positional.join(" "),
+            task.name,
+            named.join(" ")
+        ));
+    }
+    if !def.matrix.is_empty() {
+        out.push_str("#\n# The task's matrix is not exported; it runs once with the given args.\n");
+    }
+    out.push_str("set -e\n\n");
+    out.push_str(SCRIPT_HELPERS);
+
+    // expand: placeholders in length order, like `task::expand_command`
+    let mut by_len = names.clone();
+    by_len.sort_by_key(|n| std::cmp::Reverse(n.len()));
+    out.push_str("\nexpand() {\n  expanded=$1\n");
+    let passthrough = task::takes_passthrough(def);
+    if passthrough {
+        out.push_str("  [ -z \"$has_passthrough\" ] || replace '$MUU_ARGS' \"$passthrough\"\n");
+    }
+    for name in &by_len {
+        out.push_str(&format!(
+            "  replace {} \"${}\"\n",
+            shell_quote(&format!("${name}")),
+            var(name)
+        ));
+    }
+    if passthrough {
+        out.push_str("  [ -z \"$has_passthrough\" ] || replace '$@' \"$passthrough\"\n");
+    }
+    out.push_str("}\n");
+
+    script_args(task, sh, &mut out);
+
+    if !def.status.is_empty() {
+        out.push_str("\nup_to_date() {\n");
+        for check in &def.status {
+            out.push_str(&format!(
+                "  expand {}\n  {sh} -c \"$expanded\" </dev/null >/dev/null 2>&1 || return 1\n",
+                shell_quote(check)
+            ));
+        }
+        out.push_str(&format!(
+            "}}\nif up_to_date; then\n  printf '\\033[32mtask '\\''%s'\\'' is up to date\\033[0m\\n' {} >&2\n  exit 0\nfi\n",
+            shell_quote(&task.name)
+        ));
+    }
+
+    if !def.requires.is_empty() {
+        out.push_str("\n# Only presence on PATH is checked, not versions\nmissing=\n");
+        for req in &def.requires {
+            out.push_str(&format!(
+                "command -v {} >/dev/null 2>&1 || missing=\"$missing\n  - {}: '{}' not found on PATH\"\n",
+                shell_quote(&req.tool),
+                req.to_string().replace(['"', '$', '`', '\\'], ""),
+                req.tool.replace(['"', '$', '`', '\\'], ""),
+            ));
+        }
+        out.push_str("[ -z \"$missing\" ] || die \"unmet requirements:$missing\"\n");
+    }
+
+    for pre in &def.preconditions {
+        out.push_str(&format!(
+            "\nexpand {}\n{sh} -c \"$expanded\" </dev/null >/dev/null 2>&1 || die ",
+            shell_quote(&pre.sh)
+        ));
+        match pre.msg {
+            Some(ref msg) => out.push_str(&format!("{} 3\n", shell_quote(msg))),
+            None => out.push_str("\"precondition failed: $expanded\" 3\n"),
+        }
+    }
+
+    out.push('\n');
+    match def.script {
+        Some(ref path) => {
+            let path = shell_quote(&path.display().to_string());
+            // A variadic value and the words after `--` are quoted word
+            // lists, passed on as separate args
+            let words = match variadic {
+                Some(name) => format!("${} $passthrough", var(name)),
+                None => "$passthrough".to_string(),
+            };
+            let args: Vec<String> = names
+                .iter()
+                .filter(|n| variadic != Some(**n))
+                .map(|n| format!("\"${}\"", var(n)))
+                .chain(std::iter::once("\"$@\"".to_string()))
+                .collect();
+            out.push_str(&format!(
+                concat!(
+                    "eval \"set -- {words}\"\n",
+                    "shown={path}\n",
+                    "for a in {args}; do shown=\"$shown $a\"; done\n",
+                    "printf '\\033[35m$\\033[0m %s\\n' \"$shown\" >&2\n",
+                    "exec {path} {args}\n"
+                ),
+                words = words,
+                path = path,
+                args = args.join(" ")
+            ));
+        }
+        None => {
+            out.push_str(&format!(
+                "expand {}\n",
+                shell_quote(&task::command_with_passthrough(def))
+            ));
+            out.push_str("printf '\\033[35m$\\033[0m %s\\n' \"$expanded\" >&2\n");
+            out.push_str(&format!("exec {sh} -c \"set -e\n$expanded\"\n"));
+        }
+    }
+    out
+}
+
+/// The arg handling of [`script`]: collect named and positional values,
+/// fill the slots, apply defaults and check what muu checks.
+fn script_args(task: &ResolvedTask, sh: &str, out: &mut String) {
+    let def = &task.def;
+    let index = |name: &str| def.args.get_index_of(name).unwrap_or(0) + 1;
+    let var = |name: &str| format!("arg_{}", index(name));
+    let given = |name: &str| format!("given_{}", index(name));
+    let is_flag = |arg: &ArgDef| arg.kind == ArgType::Flag;
+    // Flags are only ever named, so they take no positional slot
+    let slots: Vec<(&String, &ArgDef)> = def.args.iter().filter(|(_, a)| !is_flag(a)).collect();
+    let variadic = slots.iter().any(|(_, a)| a.variadic);
+    let pattern = |names: Vec<&String>| {
+        names
+            .into_iter()
+            .map(|n| shell_quote(n))
+            .collect::<Vec<_>>()
+            .join("|")
+    };
+
+    // Words after `--` are kept, quoted, for tasks that take them
+    let after_dashes = if task::takes_passthrough(def) {
+        "--) has_passthrough=1; for a in \"$@\"; do passthrough=\"$passthrough${passthrough:+ }$(quote \"$a\")\"; done; break ;;".to_string()
+    } else {
+        let message = MuuError::PassthroughNotAccepted {
+            task: task.name.clone(),
+        };
+        format!("--) die {} ;;", shell_quote(&message.to_string()))
+    };
+
+    out.push_str("\npassthrough=\nhas_passthrough=\n");
+    for (name, arg) in &def.args {
+        if arg.variadic {
+            out.push_str(&format!("{}=\ncount=0\n", var(name)));
+        } else {
+            out.push_str(&format!("{}=\n{}=\n", var(name), given(name)));
+        }
+    }
+    out.push_str(SCRIPT_ARG_HELPERS);
+
+    // named: set an arg given by name; strict mode keeps the last value
+    out.push_str("\nnamed() {\n  case $1 in\n");
+    for (name, arg) in &def.args {
+        let set = if arg.variadic {
+            format!(
+                "{v}=\"${v}${{{v}:+ }}$(quote \"$2\")\"; count=$((count + 1))",
+                v = var(name)
+            )
+        } else if def.strict_args {
+            format!("{}=$2; {}=1", var(name), given(name))
+        } else {
+            format!(
+                "[ -z \"${g}\" ] || die \"argument '{name}' given more than once\"; {}=$2; {g}=1",
+                var(name),
+                g = given(name)
+            )
+        };
+        out.push_str(&format!("    {}) {set} ;;\n", shell_quote(name)));
+    }
+    out.push_str("    *) die \"unknown argument '$1'\" ;;\n  esac\n}\n\n");
+
+    let flags: Vec<&String> = def
+        .args
+        .iter()
+        .filter(|(_, a)| is_flag(a))
+        .map(|(n, _)| n)
+        .collect();
+    let bare_flags = |indent: &str| {
+        let mut text = String::new();
+        for name in &flags {
+            text.push_str(&format!("{indent}{}) value=true ;;\n", shell_quote(name)));
+            text.push_str(&format!(
+                "{indent}{}) key={}; value=false ;;\n",
+                shell_quote(&format!("no-{name}")),
+                shell_quote(name)
+            ));
+        }
+        text
+    };
+    out.push_str("positional=\n");
+    if def.strict_args {
+        out.push_str(concat!(
+            "by_name=0\nby_position=0\nfor a in \"$@\"; do\n  case $a in\n",
+            "    --) break ;;\n",
+            "    --*) by_name=1 ;;\n    *) by_position=1 ;;\n  esac\ndone\n",
+            "if [ \"$by_name\" = 1 ] && [ \"$by_position\" = 1 ]; then\n",
+            "  die \"cannot mix positional and named arguments\"\nfi\n",
+            "\nwhile [ $# -gt 0 ]; do\n  a=$1\n  shift\n  case $a in\n",
+        ));
+        out.push_str(&format!("    {after_dashes}\n"));
+        out.push_str(concat!(
+            "    --*=*) key=${a%%=*} value=${a#*=} ;;\n",
+            "    --*)\n      key=${a#--} value=\n      case $key in\n",
+        ));
+        out.push_str(&bare_flags("        "));
+        out.push_str(concat!(
+            "      esac\n      named \"$key\" \"$value\"\n      continue\n      ;;\n",
+            "    *) positional=\"$positional $(quote \"$a\")\"; continue ;;\n  esac\n",
+            "  named \"${key#--}\" \"$value\"\ndone\n",
+        ));
+    } else {
+        out.push_str("\nwhile [ $# -gt 0 ]; do\n  a=$1\n  shift\n  case $a in\n");
+        out.push_str(&format!("    {after_dashes}\n"));
+        out.push_str(concat!(
+            "    --*=*) key=${a%%=*} key=${key#--} value=${a#*=} inline=1 ;;\n",
+            "    --*) key=${a#--} inline=0 ;;\n",
+            "    -*)\n      if [ \"$a\" = - ] || is_number \"$a\"; then\n",
+            "        positional=\"$positional $(quote \"$a\")\"\n        continue\n      fi\n",
+            "      short=${a#-} inline=0\n",
+            "      case $short in *=*) value=${short#*=} short=${short%%=*} inline=1 ;; esac\n",
+            "      case $short in\n",
+        ));
+        for (name, arg) in &def.args {
+            if let Some(short) = arg.short {
+                out.push_str(&format!("        {short}) key={} ;;\n", shell_quote(name)));
+            }
+        }
+        out.push_str("        *) die \"unknown argument '-$short'\" ;;\n      esac\n      ;;\n");
+        let make_style: Vec<String> = def
+            .args
+            .keys()
+            .map(|n| format!("{}=*", shell_quote(n)))
+            .collect();
+        if !make_style.is_empty() {
+            out.push_str(&format!(
+                "    {}) key=${{a%%=*}} value=${{a#*=}} inline=1 ;;\n",
+                make_style.join("|")
+            ));
+        }
+        out.push_str(concat!(
+            "    *) positional=\"$positional $(quote \"$a\")\"; continue ;;\n  esac\n",
+            "  if [ \"$inline\" = 0 ]; then\n    case $key in\n",
+        ));
+        out.push_str(&bare_flags("      "));
+        let valued: Vec<&String> = def
+            .args
+            .iter()
+            .filter(|(_, a)| !is_flag(a))
+            .map(|(n, _)| n)
+            .collect();
+        if !valued.is_empty() {
+            out.push_str(&format!(
+                concat!(
+                    "      {})\n",
+                    "        [ $# -gt 0 ] && [ \"${{1#--}}\" = \"$1\" ] || die \"argument '$key' needs a value\"\n",
+                    "        value=$1\n        shift\n        ;;\n",
+                ),
+                pattern(valued)
+            ));
+        }
+        out.push_str(concat!(
+            "      *) die \"unknown argument '$key'\" ;;\n    esac\n  fi\n",
+            "  named \"$key\" \"$value\"\ndone\n",
+        ));
+    }
+
+    // Positional values fill the slots not given by name, in order
+    out.push_str("\neval \"set -- $positional\"\n");
+    for (name, arg) in &slots {
+        if arg.variadic {
+            out.push_str(&format!(
+                "for a in \"$@\"; do named {} \"$a\"; done\nset --\n",
+                shell_quote(name)
+            ));
+            break;
+        }
+        out.push_str(&format!(
+            "if [ -z \"${g}\" ] && [ $# -gt 0 ]; then {}=$1; {g}=1; shift; fi\n",
+            var(name),
+            g = given(name)
+        ));
+    }
+    if !variadic {
+        out.push_str(&format!(
+            "[ $# -eq 0 ] || die \"too many arguments: expected at most {n}, got $(({n} + $#))\"\n",
+            n = slots.len()
+        ));
+    }
+
+    for (name, arg) in &def.args {
+        let lines = value_checks(name, arg, sh);
+        if !lines.is_empty() {
+            out.push_str(&format!("\ncheck_{}() {{\n", index(name)));
+            for line in lines {
+                out.push_str(&format!("  {line}\n"));
+            }
+            out.push_str("}\n");
+        }
+    }
+    let check = |name: &str, value: &str| match value_checks(name, &def.args[name], sh).is_empty() {
+        true => String::new(),
+        false => format!("check_{} \"{value}\"", index(name)),
+    };
+
+    if !def.args.is_empty() {
+        out.push('\n');
+    }
+    for (name, arg) in &def.args {
+        if let Some(note) = computed_note(name, arg) {
+            out.push_str(&format!("# {note}\n"));
+        }
+        if arg.variadic {
+            if let Some(ref env) = arg.env {
+                let value = match is_shell_name(env) {
+                    true => format!("${{{env}:-}}"),
+                    false => format!("$(printenv {} || :)", shell_quote(env)),
+                };
+                // Split into words like `task::split_words` does
+                out.push_str(&format!(
+                    concat!(
+                        "if [ \"$count\" = 0 ] && [ -n \"{value}\" ]; then\n",
+                        "  eval \"set -- {value}\"\n",
+                        "  for a in \"$@\"; do named {name} \"$a\"; done\n",
+                        "fi\n"
+                    ),
+                    value = value,
+                    name = shell_quote(name)
+                ));
+            }
+            if arg.min_count > 0 {
+                out.push_str(&format!(
+                    "[ \"$count\" -ge {min} ] || die \"argument '{name}' needs at least {min} value(s), got $count\"\n",
+                    min = arg.min_count
+                ));
+            }
+            let check = check(name, "$a");
+            if !check.is_empty() {
+                out.push_str(&format!(
+                    "eval \"set -- ${}\"\nfor a in \"$@\"; do {check}; done\n",
+                    var(name)
+                ));
+            }
+            continue;
+        }
+        let v = var(name);
+        let default = match arg.default.is_empty() {
+            true => String::new(),
+            false => shell_quote(&arg.default),
+        };
+        let fallback = match arg.env {
+            // An empty variable counts as unset, like `ArgDef::env_value`
+            Some(ref env) if is_shell_name(env) => format!("${{{env}:-{default}}}"),
+            Some(ref env) => format!(
+                "$(printenv {} || :); [ -n \"${v}\" ] || {v}={default}",
+                shell_quote(env)
+            ),
+            None => default,
+        };
+        if !fallback.is_empty() {
+            out.push_str(&format!("[ -n \"${}\" ] || {v}={fallback}\n", given(name)));
+        }
+        if is_flag(arg) {
+            out.push_str(&format!(
+                concat!(
+                    "\ncase $(printf '%s' \"${v}\" | tr '[:upper:]' '[:lower:]') in\n",
+                    "  true|yes|on|1) {v}={on} ;;\n",
+                    "  false|no|off|0|'') {v}={off} ;;\n",
+                    "  *) die \"invalid value '${v}' for argument '{name}': expected true or false\" ;;\n",
+                    "esac\n"
+                ),
+                v = v,
+                on = shell_quote(&arg.flag_text(true)),
+                off = shell_quote(&arg.flag_text(false)),
+                name = name,
+            ));
+        } else if !arg.optional {
+            // A computed default is only missing when given empty
+            let guard = match arg.computed {
+                Some(_) => format!("[ -z \"${}\" ] || ", given(name)),
+                None => String::new(),
+            };
+            out.push_str(&format!(
+                "{guard}[ -n \"${v}\" ] || die \"missing required argument '{name}'\"\n"
+            ));
+        }
+        let check = check(name, &format!("${v}"));
+        if !check.is_empty() {
+            out.push_str(&format!("[ -z \"${v}\" ] || {check}\n"));
+        }
+        if arg.kind == ArgType::Bool {
+            // Checked above, so only the spelling is left to normalize
+            out.push_str(&format!(
+                concat!(
+                    "case $(printf '%s' \"${v}\" | tr '[:upper:]' '[:lower:]') in\n",
+                    "  true|yes|on|1) {v}=true ;;\n",
+                    "  false|no|off|0) {v}=false ;;\n",
+                    "esac\n"
+                ),
+                v = v
+            ));
+        }
+    }
+}
+
+/// Whether `${name}` works for the variable in sh.
+fn is_shell_name(name: &str) -> bool {
+    !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Shell lines checking `$1` like `task::check_value`; none if the arg
+/// takes any value. Flags are checked where they are mapped to their text.
+fn value_checks(name: &str, arg: &ArgDef, sh: &str) -> Vec<String> {
+    let invalid = |expected: &str| {
+        format!("die \"invalid value '$1' for argument '{name}': expected {expected}\"")
+    };
+    let mut lines = Vec::new();
+    match arg.kind {
+        ArgType::String | ArgType::Enum | ArgType::Flag => {}
+        ArgType::Int => lines.push(format!(
+            "case ${{1#[+-]}} in ''|*[!0-9]*) {} ;; esac",
+            invalid("an integer")
+        )),
+        ArgType::Float => lines.push(format!("is_number \"$1\" || {}", invalid("a number"))),
+        ArgType::Bool => lines.push(format!(
+            concat!(
+                "case $(printf '%s' \"$1\" | tr '[:upper:]' '[:lower:]') in\n",
+                "    true|yes|on|1|false|no|off|0) ;;\n",
+                "    *) {} ;;\n",
+                "  esac"
+            ),
+            invalid("true or false")
+        )),
+        ArgType::Path => {
+            if arg.must_exist {
+                lines.push(format!(
+                    "[ -e \"$1\" ] || die \"path '$1' for argument '{name}' does not exist\""
+                ));
+            }
+        }
+    }
+    let bound = match (arg.min, arg.max) {
+        (Some(min), Some(max)) => Some((
+            format!("v < {min} || v > {max}"),
+            format!("between {min} and {max}"),
+        )),
+        (Some(min), None) => Some((format!("v < {min}"), format!("at least {min}"))),
+        (None, Some(max)) => Some((format!("v > {max}"), format!("at most {max}"))),
+        (None, None) => None,
+    };
+    if let Some((test, bound)) = bound {
+        lines.push(format!(
+            concat!(
+                "if awk -v v=\"$1\" 'BEGIN {{ v += 0; exit !({test}) }}'; then\n",
+                "    die \"argument '{name}' must be {bound}, got $1\"\n",
+                "  fi"
+            ),
+            test = test,
+            name = name,
+            bound = bound
+        ));
+    }
+    if !arg.choices.is_empty() {
+        let pattern: Vec<String> = arg.choices.iter().map(|c| shell_quote(c)).collect();
+        lines.push(format!(
+            "case $1 in {}) ;; *) die \"invalid value '$1' for argument '{name}', expected one of: \"{} ;; esac",
+            pattern.join("|"),
+            shell_quote(&arg.choices.join(", "))
+        ));
+    } else if let Some(ref cmd) = arg.choices_cmd {
+        // Like `choices::fill`: the command's trimmed lines, or any value
+        // if it fails
+        lines.push(format!(
+            concat!(
+                "if out=$({sh} -c {cmd} </dev/null 2>/dev/null); then\n",
+                "    choices=$(printf '%s\\n' \"$out\" | awk '{{ sub(/^[ \\t]+/, \"\"); sub(/[ \\t]+$/, \"\") }} NF')\n",
+                "    printf '%s\\n' \"$choices\" | grep -Fqx -- \"$1\" ||\n",
+                "      die \"invalid value '$1' for argument '{name}', expected one of: $(printf '%s\\n' \"$choices\" | awk '{{ printf \"%s%s\", (NR > 1 ? \", \" : \"\"), $0 }}')\"\n",
+                "  else\n",
+                "    printf '\\033[33mwarning: choices_cmd for '\\''%s'\\'' failed; any value is accepted\\033[0m\\n' {quoted} >&2\n",
+                "  fi"
+            ),
+            sh = sh,
+            cmd = shell_quote(cmd),
+            name = name,
+            quoted = shell_quote(name)
+        ));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn substitute_placeholders() {
+        let args: IndexMap<String, ArgDef> = ["a", "ab"]
+            .iter()
+            .map(|n| {
+                (
+                    n.to_string(),
+                    ArgDef {
+                        default: String::new(),
+                        optional: false,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+        let out = substitute(
+            "echo $ab $a $HOME $",
+            &args,
+            |a| format!("<{a}>"),
+            |t| t.replace('$', "$$"),
+        );
+        assert_eq!(out, "echo <ab> <a> $$HOME $$");
+    }
+
+    fn flag(on: &str, off: &str) -> ArgDef {
+        ArgDef {
+            kind: ArgType::Flag,
+            default: "false".to_string(),
+            on: Some(on.to_string()),
+            off: Some(off.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn flags_expand_to_their_text() {
+        let release = flag("--release", "");
+        assert_eq!(
+            make_value(&release, "release"),
+            "$(if $(filter true yes on 1,$(release)),--release,)"
+        );
+        assert_eq!(
+            make_value(&flag("a,b", "$x"), "f"),
+            "$(if $(filter true yes on 1,$(f)),a$(comma)b,$$x)"
+        );
+        assert_eq!(
+            just_value(&release, "release"),
+            r#"{{ if release =~ "(?i)^(true|yes|on|1)$" { "--release" } else { "" } }}"#
+        );
+        assert_eq!(
+            actions_flag(&release, "inputs.r"),
+            "inputs.r && '--release' || ''"
+        );
+        assert_eq!(
+            actions_flag(&flag("", "--debug"), "inputs.r"),
+            "!inputs.r && '--debug' || ''"
+        );
+        assert_eq!(actions_flag(&flag("", ""), "inputs.r"), "''");
+    }
+}
@@ -0,0 +1,31 @@
+/// FNV-1a over `parts`, each followed by a 0xff byte so that `["ab", "c"]`
+/// and `["a", "bc"]` differ. Unlike `DefaultHasher` it stays the same
+/// across Rust releases, so file names derived from it can be found again
+/// by later builds of muu.
+pub fn stable_hash(parts: &[&[u8]]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let mut hash = OFFSET;
+    for part in parts {
+        for byte in part.iter().chain([&0xff]) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn hash_is_stable() {
+        // Changing these values orphans every history and cache file
+        assert_eq!(stable_hash(&[]), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash(&[b"/some/project"]), 0x42b1_eab1_20f8_79dd);
+        assert_ne!(stable_hash(&[b"ab", b"c"]), stable_hash(&[b"a", b"bc"]));
+    }
+}
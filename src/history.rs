@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::hash::stable_hash;
+
+/// Values kept per arg, newest first.
+const LIMIT: usize = 10;
+
+/// Arg values submitted in the selector for one project, per task and arg.
+/// Loaded without a file (e.g. for `--no-history`), it starts empty and
+/// `save` does nothing.
+#[derive(Debug, Default)]
+pub struct History {
+    file: Option<PathBuf>,
+    data: Data,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Data {
+    /// The project directory, to tell the files apart when looking at them.
+    #[serde(default)]
+    project: PathBuf,
+    #[serde(default)]
+    tasks: IndexMap<String, IndexMap<String, Vec<String>>>,
+}
+
+impl History {
+    /// History of the current project. A missing or unreadable file is an
+    /// empty history.
+    pub fn load() -> History {
+        match (dir(), project()) {
+            (Some(dir), Some(project)) => History::load_from(file_in(&dir, &project), project),
+            _ => History::default(),
+        }
+    }
+
+    fn load_from(file: PathBuf, project: PathBuf) -> History {
+        let data = std::fs::read_to_string(&file)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_else(|| Data {
+                project,
+                ..Default::default()
+            });
+        History {
+            file: Some(file),
+            data,
+        }
+    }
+
+    /// Earlier values of an arg, newest first.
+    pub fn values(&self, task: &str, arg: &str) -> &[String] {
+        self.data
+            .tasks
+            .get(task)
+            .and_then(|args| args.get(arg))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Put `value` first, dropping an older copy and the oldest values
+    /// beyond the limit.
+    pub fn record(&mut self, task: &str, arg: &str, value: &str) {
+        let values = self
+            .data
+            .tasks
+            .entry(task.to_string())
+            .or_default()
+            .entry(arg.to_string())
+            .or_default();
+        values.retain(|v| v != value);
+        values.insert(0, value.to_string());
+        values.truncate(LIMIT);
+    }
+
+    /// Best effort: a history that can't be written only costs a prefill.
+    pub fn save(&self) {
+        let Some(ref file) = self.file else {
+            return;
+        };
+        if let Some(dir) = file.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if let Ok(text) = serde_json::to_string_pretty(&self.data) {
+            let _ = std::fs::write(file, text + "\n");
+        }
+    }
+}
+
+/// Where all history files live, e.g. `~/.local/state/muu/history`.
+pub fn dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|d| d.join("muu").join("history"))
+}
+
+/// The history file of the current project.
+pub fn project_file() -> Option<PathBuf> {
+    Some(file_in(&dir()?, &project()?))
+}
+
+/// The directory of the nearest `muu.toml`, so the history is the same
+/// from every subdirectory; the current directory without one.
+fn project() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    Some(config::project_root(&cwd).unwrap_or(cwd))
+}
+
+fn file_in(dir: &Path, project: &Path) -> PathBuf {
+    let hash = stable_hash(&[project.as_os_str().as_encoded_bytes()]);
+    dir.join(format!("{hash:016x}.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn record_keeps_newest_first_without_duplicates() {
+        let mut history = History::default();
+        for value in ["a", "b", "a"] {
+            history.record("deploy", "env", value);
+        }
+        assert_eq!(history.values("deploy", "env"), ["a", "b"]);
+        assert!(history.values("deploy", "bucket").is_empty());
+        assert!(history.values("build", "env").is_empty());
+
+        for i in 0..LIMIT + 5 {
+            history.record("deploy", "env", &i.to_string());
+        }
+        let values = history.values("deploy", "env");
+        assert_eq!(values.len(), LIMIT);
+        assert_eq!(values[0], (LIMIT + 4).to_string());
+    }
+
+    #[test]
+    fn saved_history_loads_again() {
+        let dir = TempDir::new().unwrap();
+        let project = PathBuf::from("/some/project");
+        let file = file_in(dir.path(), &project);
+
+        let mut history = History::load_from(file.clone(), project.clone());
+        history.record("deploy", "env", "prod");
+        history.save();
+
+        let history = History::load_from(file.clone(), project.clone());
+        assert_eq!(history.values("deploy", "env"), ["prod"]);
+
+        std::fs::write(&file, "not json").unwrap();
+        assert!(
+            History::load_from(file, project)
+                .values("deploy", "env")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn history_without_file_is_not_saved() {
+        let mut history = History::default();
+        history.record("deploy", "env", "prod");
+        history.save();
+        assert_eq!(history.values("deploy", "env"), ["prod"]);
+    }
+}
@@ -0,0 +1,1557 @@
+use indexmap::IndexMap;
+
+use crate::config::{ArgDef, Precondition, TaskDef};
+use crate::task::shell_quote;
+
+/// Formats `muu import` can convert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Makefile targets
+    Make,
+    /// justfile recipes
+    Just,
+    /// Taskfile.yml tasks
+    Taskfile,
+    /// `scripts` in package.json
+    Npm,
+}
+
+impl Format {
+    /// File names looked for when no path is given, in order of preference.
+    pub fn default_files(self) -> &'static [&'static str] {
+        match self {
+            Format::Make => &["Makefile", "makefile", "GNUmakefile"],
+            Format::Just => &["justfile", "Justfile", ".justfile"],
+            Format::Taskfile => &[
+                "Taskfile.yml",
+                "Taskfile.yaml",
+                "taskfile.yml",
+                "taskfile.yaml",
+            ],
+            Format::Npm => &["package.json"],
+        }
+    }
+
+    /// Convert the source file's content. Only files that are not valid
+    /// YAML/JSON fail; anything else untranslatable becomes a TODO.
+    pub fn parse(self, content: &str) -> Result<Imported, String> {
+        match self {
+            Format::Make => Ok(parse_makefile(content)),
+            Format::Just => Ok(parse_justfile(content)),
+            Format::Taskfile => parse_taskfile(content),
+            Format::Npm => parse_package_json(content),
+        }
+    }
+}
+
+// ---------- Imported tasks ----------
+
+#[derive(Debug, Default)]
+pub struct Imported {
+    pub tasks: Vec<ImportedTask>,
+    /// Constructs that apply to the whole file rather than a single task.
+    pub todos: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct ImportedTask {
+    pub name: String,
+    pub def: TaskDef,
+    pub todos: Vec<String>,
+}
+
+impl Imported {
+    /// Every TODO as a one-line warning, prefixed with its task.
+    pub fn warnings(&self) -> Vec<String> {
+        let tasks = self.tasks.iter().flat_map(|t| {
+            t.todos
+                .iter()
+                .map(move |todo| format!("{}: {todo}", t.name))
+        });
+        self.todos.iter().cloned().chain(tasks).collect()
+    }
+
+    /// Render as muu.toml content, with TODOs kept as comments.
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        for todo in &self.todos {
+            out.push_str(&format!("# TODO: {todo}\n"));
+        }
+        for task in &self.tasks {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            render_task(&mut out, task);
+        }
+        out
+    }
+
+    fn push(&mut self, name: &str, def: TaskDef, todos: Vec<String>) {
+        if self.tasks.iter().any(|t| t.name == name) {
+            self.todos.push(format!(
+                "`{name}` is defined more than once; only the first was imported"
+            ));
+            return;
+        }
+        self.tasks.push(ImportedTask {
+            name: name.to_string(),
+            def,
+            todos,
+        });
+    }
+}
+
+fn render_task(out: &mut String, task: &ImportedTask) {
+    let def = &task.def;
+    out.push_str(&format!("[tasks.{}]\n", key(&task.name)));
+    for todo in &task.todos {
+        out.push_str(&format!("# TODO: {todo}\n"));
+    }
+    out.push_str(&format!("cmd = {}\n", string(&def.cmd)));
+    if let Some(ref desc) = def.description {
+        out.push_str(&format!("description = {}\n", string(desc)));
+    }
+    if !def.aliases.is_empty() {
+        out.push_str(&format!("aliases = {}\n", list(&def.aliases)));
+    }
+    if !def.tags.is_empty() {
+        out.push_str(&format!("tags = {}\n", list(&def.tags)));
+    }
+    if !def.args.is_empty() {
+        let args: Vec<String> = def
+            .args
+            .iter()
+            .map(|(name, arg)| {
+                let value = match (arg.variadic, arg.min_count) {
+                    (true, 0) => "{ variadic = true }".to_string(),
+                    (true, min) => format!("{{ variadic = true, min_count = {min} }}"),
+                    _ if arg.optional && arg.default.is_empty() => string("?"),
+                    _ => string(&arg.default),
+                };
+                format!("{} = {value}", key(name))
+            })
+            .collect();
+        out.push_str(&format!("args = {{ {} }}\n", args.join(", ")));
+    }
+    if !def.preconditions.is_empty() {
+        let checks: Vec<String> = def
+            .preconditions
+            .iter()
+            .map(|p| match p.msg {
+                Some(ref msg) => format!("{{ sh = {}, msg = {} }}", string(&p.sh), string(msg)),
+                None => format!("{{ sh = {} }}", string(&p.sh)),
+            })
+            .collect();
+        out.push_str(&format!("preconditions = [{}]\n", checks.join(", ")));
+    }
+    if !def.status.is_empty() {
+        out.push_str(&format!("status = {}\n", list(&def.status)));
+    }
+}
+
+fn key(k: &str) -> String {
+    let bare = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    if !k.is_empty() && k.chars().all(bare) {
+        k.to_string()
+    } else {
+        string(k)
+    }
+}
+
+/// A TOML string; multi-line values become literal `'''` blocks so
+/// commands stay readable.
+fn string(s: &str) -> String {
+    let literal_ok = |c: char| c == '\n' || c == '\t' || !c.is_control();
+    if s.contains('\n') && !s.contains("'''") && s.chars().all(literal_ok) {
+        format!("'''\n{s}\n'''")
+    } else {
+        toml::Value::String(s.to_string()).to_string()
+    }
+}
+
+fn list(items: &[String]) -> String {
+    let items: Vec<String> = items.iter().map(|i| string(i)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+// ---------- Shared recipe helpers ----------
+
+/// Run another imported task, the equivalent of a dependency.
+fn dep_line(name: &str) -> String {
+    format!("muu {}", shell_quote(name))
+}
+
+/// Strip make/just line prefixes: `@` only silences echoing, which muu
+/// doesn't do per line, and `-` ignores errors.
+fn recipe_line(line: &str) -> String {
+    let mut line = line;
+    let mut ignore_errors = false;
+    loop {
+        if let Some(rest) = line.strip_prefix('@') {
+            line = rest;
+        } else if let Some(rest) = line.strip_prefix('-') {
+            line = rest;
+            ignore_errors = true;
+        } else {
+            break;
+        }
+    }
+    let line = line.trim_start();
+    if ignore_errors {
+        format!("{line} || true")
+    } else {
+        line.to_string()
+    }
+}
+
+/// make and just run each recipe line in its own shell, while muu runs the
+/// whole `cmd` in one, so a `cd` now carries over to the following lines.
+fn check_cd(lines: &[String], todos: &mut Vec<String>) {
+    let before_last = &lines[..lines.len().saturating_sub(1)];
+    if before_last.iter().any(|l| l.starts_with("cd ")) {
+        todos.push(
+            "recipe lines ran in separate shells; `cd` now affects the lines after it".into(),
+        );
+    }
+}
+
+/// Record `name` as an arg of the task, optional when its default is empty.
+fn add_arg(args: &mut IndexMap<String, ArgDef>, name: &str, default: &str) {
+    args.entry(name.to_string()).or_insert_with(|| ArgDef {
+        default: default.to_string(),
+        optional: default.is_empty(),
+        ..Default::default()
+    });
+}
+
+/// Replace `{{ expr }}` interpolations using `convert`, which returns `None`
+/// for expressions it cannot translate; those are kept and reported.
+fn interpolate(
+    line: &str,
+    todos: &mut Vec<String>,
+    mut convert: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let end = start + len + 2;
+        out.push_str(&rest[..start]);
+        let expr = rest[start + 2..end - 2].trim();
+        match convert(expr) {
+            Some(value) => out.push_str(&value),
+            None => {
+                todos.push(format!("expression `{}` kept as is", &rest[start..end]));
+                out.push_str(&rest[start..end]);
+            }
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_ident(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+// ---------- Makefile ----------
+
+const MAKE_DIRECTIVES: &[&str] = &[
+    "include", "-include", "sinclude", "ifeq", "ifneq", "ifdef", "ifndef", "else", "endif",
+    "define", "endef", "export", "unexport", "override", "vpath",
+];
+
+struct Rule {
+    names: Vec<String>,
+    deps: Vec<String>,
+    description: Option<String>,
+    recipe: Vec<String>,
+}
+
+fn parse_makefile(content: &str) -> Imported {
+    let mut imported = Imported::default();
+    let mut vars: IndexMap<String, String> = IndexMap::new();
+    let mut rules: Vec<Rule> = Vec::new();
+    let mut comment: Option<String> = None;
+    let mut in_rule = false;
+    // Recipe lines of a rule that was skipped are skipped with it
+    let mut in_skipped = false;
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let raw = lines[i];
+        i += 1;
+
+        if let Some(line) = raw.strip_prefix('\t')
+            && in_rule
+        {
+            let recipe = &mut rules.last_mut().expect("in_rule implies a rule").recipe;
+            // A continued line is still a single shell command
+            match recipe.last_mut() {
+                Some(prev) if prev.ends_with('\\') => {
+                    prev.push('\n');
+                    prev.push_str(line);
+                }
+                _ => recipe.push(line.to_string()),
+            }
+            continue;
+        }
+        if raw.starts_with('\t') && in_skipped {
+            continue;
+        }
+
+        let mut line = raw.trim().to_string();
+        while line.ends_with('\\') && i < lines.len() {
+            line.pop();
+            line.push(' ');
+            line.push_str(lines[i].trim());
+            i += 1;
+        }
+        if line.is_empty() {
+            comment = None;
+            continue;
+        }
+        if let Some(text) = line.strip_prefix('#') {
+            comment = Some(text.trim_start_matches('#').trim().to_string());
+            continue;
+        }
+        in_rule = false;
+        in_skipped = false;
+        let description = comment.take();
+
+        let first = line.split_whitespace().next().unwrap_or_default();
+        if let Some((name, op, value)) = make_assignment(&line) {
+            match op {
+                "+=" => imported
+                    .todos
+                    .push(format!("appending to `{name}` is not supported: `{line}`")),
+                "!=" => {
+                    imported.todos.push(format!(
+                        "`{name}` is set from a shell command; give it a default by hand"
+                    ));
+                    vars.insert(name, String::new());
+                }
+                _ => {
+                    vars.insert(name, value);
+                }
+            }
+            continue;
+        }
+        if MAKE_DIRECTIVES.contains(&first) {
+            imported
+                .todos
+                .push(format!("directive `{line}` is not supported"));
+            continue;
+        }
+
+        let Some((head, rest)) = line.split_once(':') else {
+            imported.todos.push(format!("unrecognized line `{line}`"));
+            continue;
+        };
+        let rest = rest.strip_prefix(':').unwrap_or(rest);
+        if head.trim_start().starts_with('.') {
+            // Special targets like .PHONY
+            in_skipped = true;
+            continue;
+        }
+        if head.contains('%') {
+            imported
+                .todos
+                .push(format!("pattern rule `{}` skipped", head.trim()));
+            in_skipped = true;
+            continue;
+        }
+        let (rest, inline_desc) = match rest.split_once("##") {
+            Some((rest, desc)) => (rest, Some(desc.trim().to_string())),
+            None => (rest, None),
+        };
+        if rest.contains('=') {
+            imported
+                .todos
+                .push(format!("target-specific variable `{line}` skipped"));
+            in_skipped = true;
+            continue;
+        }
+        let (rest, inline_recipe) = match rest.split_once(';') {
+            Some((rest, recipe)) => (rest, Some(recipe.trim().to_string())),
+            None => (rest, None),
+        };
+        // Order-only prerequisites after `|` are just prerequisites here
+        let deps = rest
+            .split_whitespace()
+            .filter(|d| *d != "|")
+            .map(str::to_string)
+            .collect();
+        rules.push(Rule {
+            names: head.split_whitespace().map(str::to_string).collect(),
+            deps,
+            description: inline_desc.or(description),
+            recipe: inline_recipe.into_iter().collect(),
+        });
+        in_rule = true;
+    }
+
+    let targets: Vec<&String> = rules.iter().flat_map(|r| &r.names).collect();
+    for rule in &rules {
+        for name in &rule.names {
+            let mut todos = Vec::new();
+            let mut args = IndexMap::new();
+            let mut lines = Vec::new();
+            for dep in &rule.deps {
+                if targets.contains(&dep) {
+                    lines.push(dep_line(dep));
+                } else {
+                    todos.push(format!(
+                        "prerequisite `{dep}` is a file, not a target; skipped"
+                    ));
+                }
+            }
+            for line in &rule.recipe {
+                let line = line.strip_prefix('+').unwrap_or(line);
+                let line = make_refs(&recipe_line(line), name, &vars, &mut args, &mut todos);
+                lines.push(line);
+            }
+            if lines.is_empty() {
+                continue;
+            }
+            check_cd(&lines, &mut todos);
+            for (arg, def) in &args {
+                if def.default.contains('$') {
+                    todos.push(format!("default for `{arg}` references other variables"));
+                }
+            }
+            let def = TaskDef {
+                cmd: lines.join("\n"),
+                description: rule.description.clone(),
+                args,
+                ..Default::default()
+            };
+            imported.push(name, def, todos);
+        }
+    }
+    imported
+}
+
+/// Split `NAME = value` (or `:=`, `::=`, `?=`, `+=`, `!=`) into name,
+/// operator and value. Rules such as `build: FOO=1` are not assignments.
+fn make_assignment(line: &str) -> Option<(String, &'static str, String)> {
+    let eq = line.find('=')?;
+    let before = &line[..eq];
+    let (name, op) = if let Some(name) = before.strip_suffix("::") {
+        (name, "::=")
+    } else if let Some(name) = before.strip_suffix(':') {
+        (name, ":=")
+    } else if let Some(name) = before.strip_suffix('?') {
+        (name, "?=")
+    } else if let Some(name) = before.strip_suffix('+') {
+        (name, "+=")
+    } else if let Some(name) = before.strip_suffix('!') {
+        (name, "!=")
+    } else {
+        (before, "=")
+    };
+    if name.contains(':') {
+        return None;
+    }
+    let name = name.trim();
+    let name = name
+        .strip_prefix("export ")
+        .or_else(|| name.strip_prefix("override "))
+        .unwrap_or(name)
+        .trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((name.to_string(), op, line[eq + 1..].trim().to_string()))
+}
+
+/// Translate `$(VAR)` references: Makefile variables become args, anything
+/// else is assumed to come from the environment, as it does in make.
+fn make_refs(
+    line: &str,
+    target: &str,
+    vars: &IndexMap<String, String>,
+    args: &mut IndexMap<String, ArgDef>,
+    todos: &mut Vec<String>,
+) -> String {
+    let mut var = |name: &str, original: &str, todos: &mut Vec<String>| -> String {
+        if name.contains(|c: char| c.is_whitespace() || c == ',' || c == ':') {
+            todos.push(format!("make function `{original}` kept as is"));
+            return original.to_string();
+        }
+        match name {
+            "MAKE" => "make".to_string(),
+            "CURDIR" => "$PWD".to_string(),
+            _ => match vars.get(name) {
+                Some(value) => {
+                    let arg = name.to_lowercase();
+                    add_arg(args, &arg, value);
+                    format!("${arg}")
+                }
+                None => format!("${{{name}}}"),
+            },
+        }
+    };
+
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+        let Some(c) = after.chars().next() else {
+            out.push('$');
+            rest = after;
+            break;
+        };
+        match c {
+            '$' => {
+                out.push('$');
+                rest = &after[1..];
+            }
+            '(' | '{' => {
+                let close = if c == '(' { ')' } else { '}' };
+                match after.find(close) {
+                    Some(end) => {
+                        out.push_str(&var(&after[1..end], &rest[i..i + end + 2], todos));
+                        rest = &after[end + 1..];
+                    }
+                    None => {
+                        out.push('$');
+                        rest = after;
+                    }
+                }
+            }
+            '@' => {
+                out.push_str(target);
+                rest = &after[1..];
+            }
+            '<' | '^' | '?' | '*' | '+' | '|' => {
+                todos.push(format!("automatic variable `${c}` kept as is"));
+                out.push('$');
+                out.push(c);
+                rest = &after[1..];
+            }
+            c if c.is_ascii_alphanumeric() => {
+                out.push_str(&var(&after[..1], &rest[i..i + 2], todos));
+                rest = &after[1..];
+            }
+            _ => {
+                out.push('$');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// ---------- justfile ----------
+
+struct Recipe {
+    name: String,
+    params: IndexMap<String, ArgDef>,
+    before: Vec<String>,
+    after: Vec<String>,
+    description: Option<String>,
+    aliases: Vec<String>,
+    tags: Vec<String>,
+    body: Vec<String>,
+    todos: Vec<String>,
+    /// Parameters whose default is a justfile variable, with its name.
+    var_defaults: Vec<(String, String)>,
+}
+
+fn parse_justfile(content: &str) -> Imported {
+    let mut imported = Imported::default();
+    let mut vars: IndexMap<String, String> = IndexMap::new();
+    let mut aliases: Vec<(String, String)> = Vec::new();
+    let mut recipes: Vec<Recipe> = Vec::new();
+    let mut comment: Option<String> = None;
+    let mut attrs: Vec<String> = Vec::new();
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        i += 1;
+        if line.is_empty() {
+            comment = None;
+            continue;
+        }
+        if let Some(text) = line.strip_prefix('#') {
+            comment = Some(text.trim().to_string());
+            continue;
+        }
+        if let Some(attr) = line.strip_prefix('[').and_then(|a| a.strip_suffix(']')) {
+            attrs.extend(attr.split(',').map(|a| a.trim().to_string()));
+            continue;
+        }
+
+        let first = line.split_whitespace().next().unwrap_or_default();
+        match first {
+            "set" | "import" | "mod" | "export" | "unexport" => {
+                imported.todos.push(format!("`{line}` is not supported"));
+                continue;
+            }
+            "alias" => {
+                match line["alias".len()..].split_once(":=") {
+                    Some((alias, target)) => {
+                        aliases.push((alias.trim().to_string(), target.trim().to_string()));
+                    }
+                    None => imported.todos.push(format!("unrecognized alias `{line}`")),
+                }
+                continue;
+            }
+            _ => {}
+        }
+        if let Some((name, value)) = line.split_once(":=")
+            && is_ident(name.trim())
+        {
+            let name = name.trim().to_string();
+            match unquote(value.trim()) {
+                Some(value) => {
+                    vars.insert(name, value);
+                }
+                None => {
+                    imported.todos.push(format!(
+                        "variable `{name}` has a computed value; give it a default by hand"
+                    ));
+                    vars.insert(name, String::new());
+                }
+            }
+            continue;
+        }
+
+        let Some(mut recipe) = just_header(line) else {
+            imported.todos.push(format!("unrecognized line `{line}`"));
+            continue;
+        };
+        recipe.description = comment.take();
+        for attr in attrs.drain(..) {
+            let value = attr
+                .split_once('(')
+                .and_then(|(_, v)| v.strip_suffix(')'))
+                .and_then(|v| unquote(v.trim()));
+            match (attr.split('(').next().unwrap_or_default(), value) {
+                ("doc", Some(doc)) => recipe.description = Some(doc),
+                ("group", Some(group)) => recipe.tags.push(group),
+                _ => recipe
+                    .todos
+                    .push(format!("attribute `[{attr}]` is not supported")),
+            }
+        }
+
+        let mut indent = None;
+        while i < lines.len() {
+            let body = lines[i];
+            if !body.trim().is_empty() && !body.starts_with([' ', '\t']) {
+                break;
+            }
+            i += 1;
+            if body.trim().is_empty() {
+                recipe.body.push(String::new());
+                continue;
+            }
+            let indent = *indent.get_or_insert_with(|| body.len() - body.trim_start().len());
+            let stripped = body.get(indent..).unwrap_or(body.trim_start());
+            recipe.body.push(stripped.to_string());
+        }
+        while recipe.body.last().is_some_and(String::is_empty) {
+            recipe.body.pop();
+        }
+        recipes.push(recipe);
+    }
+
+    for (alias, target) in aliases {
+        match recipes.iter_mut().find(|r| r.name == target) {
+            Some(recipe) => recipe.aliases.push(alias),
+            None => imported.todos.push(format!(
+                "alias `{alias}` points to unknown recipe `{target}`"
+            )),
+        }
+    }
+
+    for recipe in recipes {
+        let Recipe {
+            name,
+            mut params,
+            before,
+            after,
+            description,
+            aliases,
+            tags,
+            body,
+            mut todos,
+            var_defaults,
+        } = recipe;
+
+        for (param, var) in var_defaults {
+            match vars.get(&var) {
+                Some(value) => params[&param].default = value.clone(),
+                None => todos.push(format!(
+                    "parameter `{param}` defaults to `{var}`, which is not a variable in this file"
+                )),
+            }
+        }
+
+        let mut lines: Vec<String> = before.iter().map(|d| dep_line(d)).collect();
+        if body.first().is_some_and(|l| l.starts_with("#!")) {
+            todos.push(format!(
+                "shebang recipe (`{}`); consider moving it to a script in scripts_dir",
+                body[0]
+            ));
+            lines.extend(body[1..].iter().cloned());
+        } else {
+            let mut body_lines = Vec::new();
+            for line in &body {
+                let line = interpolate(&recipe_line(line), &mut todos, |expr| {
+                    if params.contains_key(expr) {
+                        Some(format!("${expr}"))
+                    } else if let Some(value) = vars.get(expr) {
+                        add_arg(&mut params, expr, value);
+                        Some(format!("${expr}"))
+                    } else {
+                        None
+                    }
+                });
+                body_lines.push(line);
+            }
+            check_cd(&body_lines, &mut todos);
+            lines.extend(body_lines);
+        }
+        lines.extend(after.iter().map(|d| dep_line(d)));
+        if lines.iter().all(String::is_empty) {
+            continue;
+        }
+        let def = TaskDef {
+            cmd: lines.join("\n"),
+            description,
+            args: params,
+            aliases,
+            tags,
+            ..Default::default()
+        };
+        imported.push(&name, def, todos);
+    }
+    imported
+}
+
+/// Parse `@name param="default" +rest: dep1 dep2 && after`.
+fn just_header(line: &str) -> Option<Recipe> {
+    let line = line.strip_prefix('@').unwrap_or(line);
+    let words = split_words(line);
+    let colon = words.iter().position(|w| w == ":" || w.ends_with(':'))?;
+    let mut head: Vec<String> = words[..=colon].to_vec();
+    let last = head.last_mut()?;
+    last.pop();
+    if last.is_empty() {
+        head.pop();
+    }
+    let (name, params) = head.split_first()?;
+    if !is_ident(name) {
+        return None;
+    }
+
+    let mut recipe = Recipe {
+        name: name.clone(),
+        params: IndexMap::new(),
+        before: Vec::new(),
+        after: Vec::new(),
+        description: None,
+        aliases: Vec::new(),
+        tags: Vec::new(),
+        body: Vec::new(),
+        todos: Vec::new(),
+        var_defaults: Vec::new(),
+    };
+    for param in params {
+        let param = param.strip_prefix('$').unwrap_or(param);
+        let (param, variadic) = match param.strip_prefix(['+', '*']) {
+            Some(rest) => (rest, param.chars().next()),
+            None => (param, None),
+        };
+        let (pname, default) = match param.split_once('=') {
+            // A bare name is a variable, resolved once the whole file is read
+            Some((pname, var)) if is_ident(var) => {
+                recipe
+                    .var_defaults
+                    .push((pname.to_string(), var.to_string()));
+                (pname, String::new())
+            }
+            Some((pname, default)) => match unquote(default) {
+                Some(default) => (pname, default),
+                None => {
+                    recipe.todos.push(format!(
+                        "parameter `{pname}` has a computed default `{default}`"
+                    ));
+                    (pname, String::new())
+                }
+            },
+            None => (param, String::new()),
+        };
+        let arg = match variadic {
+            Some(kind) => {
+                if !default.is_empty() || recipe.var_defaults.iter().any(|(p, _)| p == pname) {
+                    recipe.todos.push(format!(
+                        "variadic parameter `{kind}{pname}` lost its default; muu's variadic args can't have one"
+                    ));
+                    recipe.var_defaults.retain(|(p, _)| p != pname);
+                }
+                let min_count = usize::from(kind == '+');
+                ArgDef {
+                    optional: min_count == 0,
+                    variadic: true,
+                    min_count,
+                    ..Default::default()
+                }
+            }
+            None => ArgDef {
+                default,
+                ..Default::default()
+            },
+        };
+        recipe.params.insert(pname.to_string(), arg);
+    }
+
+    let mut after_and = false;
+    for dep in &words[colon + 1..] {
+        if dep == "&&" {
+            after_and = true;
+        } else if is_ident(dep) {
+            if after_and {
+                recipe.after.push(dep.clone());
+            } else {
+                recipe.before.push(dep.clone());
+            }
+        } else {
+            recipe
+                .todos
+                .push(format!("dependency `{dep}` with arguments skipped"));
+        }
+    }
+    Some(recipe)
+}
+
+/// Split on whitespace, keeping quoted strings and `(dep arg)` groups intact.
+fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote = None;
+    let mut depth = 0;
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => {
+                quote = None;
+                word.push(c);
+            }
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'' | '`') => {
+                quote = Some(c);
+                word.push(c);
+            }
+            (None, '(') => {
+                depth += 1;
+                word.push(c);
+            }
+            (None, ')') => {
+                depth -= 1;
+                word.push(c);
+            }
+            (None, c) if c.is_whitespace() && depth == 0 => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            (None, c) => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// The value of a plain string literal (or bare word); `None` for
+/// backticks and expressions.
+fn unquote(s: &str) -> Option<String> {
+    for q in ['"', '\''] {
+        if let Some(inner) = s.strip_prefix(q).and_then(|s| s.strip_suffix(q)) {
+            return Some(inner.to_string());
+        }
+    }
+    let literal = |c: char| c.is_ascii_alphanumeric() || "-_./:@".contains(c);
+    s.chars().all(literal).then(|| s.to_string())
+}
+
+// ---------- Taskfile ----------
+
+const TASKFILE_TOP_LEVEL: &[&str] = &["includes", "dotenv", "env", "output", "set", "shopt"];
+
+fn parse_taskfile(content: &str) -> Result<Imported, String> {
+    let doc: serde_norway::Value = serde_norway::from_str(content).map_err(|e| e.to_string())?;
+    let mut imported = Imported::default();
+    for key in TASKFILE_TOP_LEVEL {
+        if doc.get(key).is_some() {
+            imported
+                .todos
+                .push(format!("top-level `{key}` is not supported"));
+        }
+    }
+    let global_vars = yaml_vars(doc.get("vars"), &mut imported.todos);
+
+    let Some(tasks) = doc.get("tasks").and_then(|t| t.as_mapping()) else {
+        return Ok(imported);
+    };
+    // Names and aliases a `task:` call or dependency can refer to
+    let mut known: Vec<&str> = Vec::new();
+    for (name, task) in tasks {
+        known.extend(name.as_str());
+        if let Some(aliases) = task.get("aliases").and_then(|a| a.as_sequence()) {
+            known.extend(aliases.iter().filter_map(|a| a.as_str()));
+        }
+    }
+    for (name, task) in tasks {
+        let Some(name) = name.as_str() else {
+            continue;
+        };
+        let (def, todos) = taskfile_task(name, task, &global_vars, &known);
+        if def.cmd.is_empty() {
+            continue;
+        }
+        imported.push(name, def, todos);
+    }
+    Ok(imported)
+}
+
+fn taskfile_task(
+    name: &str,
+    task: &serde_norway::Value,
+    global_vars: &IndexMap<String, String>,
+    known: &[&str],
+) -> (TaskDef, Vec<String>) {
+    let mut todos = Vec::new();
+    let mut def = TaskDef::default();
+
+    // Short forms: `build: go build` and `build: [go vet, go build]`
+    let task = match task {
+        serde_norway::Value::Mapping(_) => task.clone(),
+        cmds => {
+            let mut map = serde_norway::Mapping::new();
+            map.insert("cmds".into(), cmds.clone());
+            serde_norway::Value::Mapping(map)
+        }
+    };
+    let mut vars = global_vars.clone();
+    vars.extend(yaml_vars(task.get("vars"), &mut todos));
+    let required: Vec<String> = task
+        .get("requires")
+        .and_then(|r| r.get("vars"))
+        .map(yaml_strings)
+        .unwrap_or_default();
+    for var in &required {
+        def.args.insert(
+            var.to_lowercase(),
+            ArgDef {
+                default: String::new(),
+                optional: false,
+                ..Default::default()
+            },
+        );
+    }
+
+    let template = |s: &str, args: &mut IndexMap<String, ArgDef>, todos: &mut Vec<String>| {
+        interpolate(s, todos, |expr| {
+            let var = expr.strip_prefix('.').filter(|v| is_ident(v))?;
+            if var == "TASK" {
+                return Some(name.to_string());
+            }
+            let value = vars.get(var).cloned();
+            if value.is_none() && !required.iter().any(|r| r == var) {
+                return None;
+            }
+            let arg = var.to_lowercase();
+            add_arg(args, &arg, &value.unwrap_or_default());
+            Some(format!("${arg}"))
+        })
+    };
+
+    def.description = ["desc", "summary"]
+        .iter()
+        .find_map(|k| task.get(k).and_then(|v| v.as_str()))
+        .map(|d| d.trim().to_string());
+    def.aliases = task.get("aliases").map(yaml_strings).unwrap_or_default();
+
+    let mut lines = Vec::new();
+    if let Some(dir) = task.get("dir").and_then(|d| d.as_str()) {
+        let dir = template(dir, &mut def.args, &mut todos);
+        lines.push(format!(
+            "cd {}",
+            if dir.contains('$') {
+                dir
+            } else {
+                shell_quote(&dir)
+            }
+        ));
+    }
+    if let Some(env) = task.get("env").and_then(|e| e.as_mapping()) {
+        for (key, value) in env {
+            let (Some(key), Some(value)) = (key.as_str(), yaml_scalar(value)) else {
+                todos.push("dynamic `env` value skipped".into());
+                continue;
+            };
+            let value = template(&value, &mut def.args, &mut todos);
+            lines.push(format!("export {key}={}", shell_quote(&value)));
+        }
+    }
+    for dep in task
+        .get("deps")
+        .and_then(|d| d.as_sequence())
+        .into_iter()
+        .flatten()
+    {
+        match dep
+            .as_str()
+            .or_else(|| dep.get("task").and_then(|t| t.as_str()))
+        {
+            Some(dep_name) => {
+                if dep.get("vars").is_some() {
+                    todos.push(format!("vars passed to dependency `{dep_name}` skipped"));
+                }
+                if !known.contains(&dep_name) {
+                    todos.push(format!(
+                        "dependency `{dep_name}` is not a task in this file"
+                    ));
+                }
+                lines.push(dep_line(dep_name));
+            }
+            None => todos.push("unrecognized dependency skipped".into()),
+        }
+    }
+    let cmds = match task.get("cmds") {
+        Some(serde_norway::Value::Sequence(cmds)) => cmds.clone(),
+        Some(cmd) => vec![cmd.clone()],
+        None => task.get("cmd").cloned().into_iter().collect(),
+    };
+    for cmd in &cmds {
+        if let Some(s) = cmd
+            .as_str()
+            .or_else(|| cmd.get("cmd").and_then(|c| c.as_str()))
+        {
+            lines.push(template(s.trim_end(), &mut def.args, &mut todos));
+        } else if let Some(other) = cmd.get("task").and_then(|t| t.as_str()) {
+            if cmd.get("vars").is_some() {
+                todos.push(format!("vars passed to `{other}` skipped"));
+            }
+            if !known.contains(&other) {
+                todos.push(format!("`task: {other}` is not a task in this file"));
+            }
+            lines.push(dep_line(other));
+        } else if cmd.get("defer").is_some() {
+            todos.push("`defer` is not supported".into());
+        } else {
+            todos.push("unrecognized command skipped".into());
+        }
+    }
+    def.cmd = lines.join("\n");
+
+    for check in task.get("status").map(yaml_strings).unwrap_or_default() {
+        def.status.push(template(&check, &mut def.args, &mut todos));
+    }
+    for check in task
+        .get("preconditions")
+        .and_then(|p| p.as_sequence())
+        .into_iter()
+        .flatten()
+    {
+        let sh = check
+            .as_str()
+            .or_else(|| check.get("sh").and_then(|s| s.as_str()));
+        let Some(sh) = sh else {
+            todos.push("unrecognized precondition skipped".into());
+            continue;
+        };
+        def.preconditions.push(Precondition {
+            sh: template(sh, &mut def.args, &mut todos),
+            msg: check
+                .get("msg")
+                .and_then(|m| m.as_str())
+                .map(str::to_string),
+        });
+    }
+
+    const HANDLED: &[&str] = &[
+        "desc",
+        "summary",
+        "aliases",
+        "vars",
+        "requires",
+        "dir",
+        "env",
+        "deps",
+        "cmds",
+        "cmd",
+        "status",
+        "preconditions",
+        "silent",
+    ];
+    for (key, _) in task.as_mapping().into_iter().flatten() {
+        if let Some(key) = key.as_str()
+            && !HANDLED.contains(&key)
+        {
+            todos.push(format!("`{key}` is not supported"));
+        }
+    }
+    (def, todos)
+}
+
+fn yaml_vars(
+    vars: Option<&serde_norway::Value>,
+    todos: &mut Vec<String>,
+) -> IndexMap<String, String> {
+    let mut out = IndexMap::new();
+    for (key, value) in vars.and_then(|v| v.as_mapping()).into_iter().flatten() {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        match yaml_scalar(value) {
+            Some(value) if !value.contains("{{") => {
+                out.insert(key.to_string(), value);
+            }
+            _ => {
+                todos.push(format!(
+                    "variable `{key}` is computed; give it a default by hand"
+                ));
+                out.insert(key.to_string(), String::new());
+            }
+        }
+    }
+    out
+}
+
+fn yaml_scalar(value: &serde_norway::Value) -> Option<String> {
+    match value {
+        serde_norway::Value::String(s) => Some(s.clone()),
+        serde_norway::Value::Number(n) => Some(n.to_string()),
+        serde_norway::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn yaml_strings(value: &serde_norway::Value) -> Vec<String> {
+    match value {
+        serde_norway::Value::Sequence(items) => items.iter().filter_map(yaml_scalar).collect(),
+        other => yaml_scalar(other).into_iter().collect(),
+    }
+}
+
+// ---------- package.json ----------
+
+/// How package managers invoke another script; rewritten to `muu <name>`.
+const RUN_PREFIXES: &[&str] = &[
+    "npm run-script ",
+    "npm run ",
+    "yarn run ",
+    "pnpm run ",
+    "bun run ",
+];
+
+fn parse_package_json(content: &str) -> Result<Imported, String> {
+    let json: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let mut imported = Imported::default();
+    let Some(scripts) = json.get("scripts").and_then(|s| s.as_object()) else {
+        return Ok(imported);
+    };
+    if json.get("dependencies").is_some() || json.get("devDependencies").is_some() {
+        imported.todos.push(
+            "npm puts node_modules/.bin on PATH; commands from local packages may need `npx`"
+                .into(),
+        );
+    }
+
+    for (name, body) in scripts {
+        let Some(body) = body.as_str() else {
+            continue;
+        };
+        let mut todos = Vec::new();
+        let mut lines = Vec::new();
+        if scripts.contains_key(&format!("pre{name}")) {
+            lines.push(dep_line(&format!("pre{name}")));
+        }
+        let mut cmd = body.to_string();
+        for prefix in RUN_PREFIXES {
+            cmd = rewrite_runs(&cmd, prefix, |script| scripts.contains_key(script));
+        }
+        if cmd.contains("$npm_") {
+            todos.push("npm-provided `$npm_*` variables are not set by muu".into());
+        }
+        lines.push(cmd);
+        if scripts.contains_key(&format!("post{name}")) {
+            lines.push(dep_line(&format!("post{name}")));
+        }
+        let def = TaskDef {
+            cmd: lines.join("\n"),
+            ..Default::default()
+        };
+        imported.push(name, def, todos);
+    }
+    Ok(imported)
+}
+
+/// Replace `<prefix><script>` with `muu <script>` where `script` is known.
+fn rewrite_runs(cmd: &str, prefix: &str, known: impl Fn(&str) -> bool) -> String {
+    let mut out = String::new();
+    let mut rest = cmd;
+    while let Some(i) = rest.find(prefix) {
+        let after = &rest[i + prefix.len()..];
+        let end = after
+            .find(|c: char| c.is_whitespace() || ";&|".contains(c))
+            .unwrap_or(after.len());
+        let script = &after[..end];
+        out.push_str(&rest[..i]);
+        if known(script) {
+            out.push_str(&dep_line(script));
+        } else {
+            out.push_str(&rest[i..i + prefix.len() + end]);
+        }
+        rest = &after[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::config::ConfigFile;
+
+    fn task<'a>(imported: &'a Imported, name: &str) -> &'a ImportedTask {
+        imported.tasks.iter().find(|t| t.name == name).unwrap()
+    }
+
+    /// The rendered TOML must load back as a muu config.
+    fn round_trip(imported: &Imported) -> ConfigFile {
+        let toml = imported.to_toml();
+        toml::from_str(&toml).unwrap_or_else(|e| panic!("{e}\n{toml}"))
+    }
+
+    #[test]
+    fn makefile_targets() {
+        let imported = parse_makefile(
+            "ENV ?= dev\n\
+             OUT := dist\n\
+             .PHONY: build deploy\n\
+             \n\
+             # Build the site\n\
+             build: lint\n\
+             \t@mkdir -p $(OUT)\n\
+             \t-rm -rf $(OUT)/*\n\
+             \tcp -r src/* $(OUT)/\n\
+             \n\
+             lint:\n\
+             \teslint .\n\
+             \n\
+             deploy: build ## Deploy to $(ENV)\n\
+             \taws s3 sync $(OUT) s3://site-$(ENV) --profile $$AWS_PROFILE --region ${REGION}\n",
+        );
+        assert!(imported.todos.is_empty(), "{:?}", imported.todos);
+
+        let build = task(&imported, "build");
+        assert_eq!(build.def.description.as_deref(), Some("Build the site"));
+        assert_eq!(
+            build.def.cmd,
+            "muu lint\nmkdir -p $out\nrm -rf $out/* || true\ncp -r src/* $out/"
+        );
+        assert_eq!(build.def.args["out"].default, "dist");
+
+        let deploy = task(&imported, "deploy");
+        assert_eq!(deploy.def.description.as_deref(), Some("Deploy to $(ENV)"));
+        assert_eq!(
+            deploy.def.cmd,
+            "muu build\naws s3 sync $out s3://site-$env --profile $AWS_PROFILE --region ${REGION}"
+        );
+        let args: Vec<&String> = deploy.def.args.keys().collect();
+        assert_eq!(args, vec!["out", "env"]);
+
+        let cfg = round_trip(&imported);
+        assert_eq!(cfg.tasks["deploy"].cmd.trim_end(), deploy.def.cmd);
+        assert_eq!(cfg.tasks["deploy"].args["env"].default, "dev");
+    }
+
+    #[test]
+    fn makefile_untranslatable() {
+        let imported = parse_makefile(
+            "include common.mk\n\
+             SRCS += main.c\n\
+             %.o: %.c\n\
+             \tcc -c $< -o $@\n\
+             app: main.o\n\
+             \tcc -o $@ $^ $(shell pkg-config --libs x)\n",
+        );
+        assert_eq!(
+            imported.warnings(),
+            vec![
+                "directive `include common.mk` is not supported",
+                "appending to `SRCS` is not supported: `SRCS += main.c`",
+                "pattern rule `%.o` skipped",
+                "app: prerequisite `main.o` is a file, not a target; skipped",
+                "app: automatic variable `$^` kept as is",
+                "app: make function `$(shell pkg-config --libs x)` kept as is",
+            ]
+        );
+        assert_eq!(
+            task(&imported, "app").def.cmd,
+            "cc -o app $^ $(shell pkg-config --libs x)"
+        );
+        let toml = imported.to_toml();
+        assert!(toml.starts_with("# TODO: directive `include common.mk` is not supported\n"));
+        assert!(toml.contains("[tasks.app]\n# TODO: prerequisite `main.o`"));
+        round_trip(&imported);
+    }
+
+    #[test]
+    fn justfile_recipes() {
+        let imported = parse_justfile(
+            "set dotenv-load\n\
+             registry := \"ghcr.io/acme\"\n\
+             alias b := build\n\
+             \n\
+             # Build the image\n\
+             [group('docker')]\n\
+             build tag=\"latest\": lint\n\
+             \x20   docker build -t {{registry}}/app:{{ tag }} .\n\
+             \n\
+             lint:\n\
+             \x20 @cargo clippy\n\
+             \n\
+             release version: (build version) && notify\n\
+             \x20 git tag {{version}}\n\
+             \x20 echo {{ uppercase(version) }}\n\
+             \n\
+             notify:\n\
+             \x20 echo done\n",
+        );
+        let build = task(&imported, "build");
+        assert_eq!(build.def.description.as_deref(), Some("Build the image"));
+        assert_eq!(build.def.aliases, vec!["b"]);
+        assert_eq!(build.def.tags, vec!["docker"]);
+        assert_eq!(
+            build.def.cmd,
+            "muu lint\ndocker build -t $registry/app:$tag ."
+        );
+        let args: Vec<(&String, &str)> = build
+            .def
+            .args
+            .iter()
+            .map(|(k, a)| (k, a.default.as_str()))
+            .collect();
+        assert_eq!(
+            args,
+            vec![
+                (&"tag".to_string(), "latest"),
+                (&"registry".to_string(), "ghcr.io/acme")
+            ]
+        );
+        assert_eq!(task(&imported, "lint").def.cmd, "cargo clippy");
+
+        let release = task(&imported, "release");
+        assert_eq!(
+            release.def.cmd,
+            "git tag $version\necho {{ uppercase(version) }}\nmuu notify"
+        );
+        assert!(!release.def.args["version"].optional);
+        assert_eq!(
+            imported.warnings(),
+            vec![
+                "`set dotenv-load` is not supported",
+                "release: dependency `(build version)` with arguments skipped",
+                "release: expression `{{ uppercase(version) }}` kept as is",
+            ]
+        );
+        round_trip(&imported);
+    }
+
+    #[test]
+    fn justfile_params() {
+        let imported = parse_justfile(
+            "default_env := \"staging\"\n\
+             \n\
+             deploy env=default_env region=zone: \n\
+             \x20 ./deploy.sh {{env}} {{region}}\n\
+             \n\
+             lint *files:\n\
+             \x20 eslint {{files}}\n\
+             \n\
+             test +files:\n\
+             \x20 jest {{files}}\n",
+        );
+        let deploy = task(&imported, "deploy");
+        assert_eq!(deploy.def.args["env"].default, "staging");
+        assert_eq!(deploy.def.args["region"].default, "");
+        assert_eq!(
+            deploy.todos,
+            vec!["parameter `region` defaults to `zone`, which is not a variable in this file"]
+        );
+
+        let lint = &task(&imported, "lint").def.args["files"];
+        assert!(lint.variadic && lint.optional);
+        assert_eq!(lint.min_count, 0);
+        let test = &task(&imported, "test").def.args["files"];
+        assert!(test.variadic && !test.optional);
+        assert_eq!(test.min_count, 1);
+        assert!(task(&imported, "test").todos.is_empty());
+        let cfg = round_trip(&imported);
+        assert!(cfg.tasks["lint"].args["files"].variadic);
+        assert_eq!(cfg.tasks["test"].args["files"].min_count, 1);
+    }
+
+    #[test]
+    fn justfile_shebang_recipe() {
+        let imported = parse_justfile("stats:\n    #!/usr/bin/env python3\n    print(1)\n");
+        let stats = task(&imported, "stats");
+        assert_eq!(stats.def.cmd, "print(1)");
+        assert!(stats.todos[0].starts_with("shebang recipe"));
+    }
+
+    #[test]
+    fn taskfile_tasks() {
+        let imported = parse_taskfile(
+            r#"
+version: '3'
+vars:
+  REGION: us-east-1
+dotenv: ['.env']
+tasks:
+  lint: golangci-lint run
+  build:
+    desc: Build the binary
+    aliases: [b]
+    deps: [lint]
+    dir: cmd/app
+    cmds:
+      - go build -o {{.OUT}} .
+      - task: lint
+    vars:
+      OUT: bin/app
+    status:
+      - test -f {{.OUT}}
+  deploy:
+    desc: Deploy
+    requires:
+      vars: [ENV]
+    preconditions:
+      - sh: test -n "$AWS_PROFILE"
+        msg: AWS_PROFILE is not set
+    sources: ['**/*.go']
+    deps: [b]
+    cmds:
+      - ./deploy.sh {{.ENV}} {{.REGION}} {{.CLI_ARGS}}
+      - task: notify
+"#,
+        )
+        .unwrap();
+        assert_eq!(task(&imported, "lint").def.cmd, "golangci-lint run");
+
+        let build = task(&imported, "build");
+        assert_eq!(build.def.description.as_deref(), Some("Build the binary"));
+        assert_eq!(build.def.aliases, vec!["b"]);
+        assert_eq!(
+            build.def.cmd,
+            "cd cmd/app\nmuu lint\ngo build -o $out .\nmuu lint"
+        );
+        assert_eq!(build.def.args["out"].default, "bin/app");
+        assert_eq!(build.def.status, vec!["test -f $out"]);
+
+        let deploy = task(&imported, "deploy");
+        assert_eq!(
+            deploy.def.cmd,
+            "muu b\n./deploy.sh $env $region {{.CLI_ARGS}}\nmuu notify"
+        );
+        assert_eq!(deploy.def.args["env"].default, "");
+        assert!(!deploy.def.args["env"].optional);
+        assert_eq!(deploy.def.args["region"].default, "us-east-1");
+        assert_eq!(
+            deploy.def.preconditions[0].msg.as_deref(),
+            Some("AWS_PROFILE is not set")
+        );
+        assert_eq!(
+            imported.warnings(),
+            vec![
+                "top-level `dotenv` is not supported",
+                "deploy: expression `{{.CLI_ARGS}}` kept as is",
+                "deploy: `task: notify` is not a task in this file",
+                "deploy: `sources` is not supported",
+            ]
+        );
+        round_trip(&imported);
+    }
+
+    #[test]
+    fn taskfile_invalid_yaml() {
+        assert!(parse_taskfile("tasks: [unclosed").is_err());
+    }
+
+    #[test]
+    fn npm_scripts() {
+        let imported = parse_package_json(
+            r#"{
+  "scripts": {
+    "prebuild": "rm -rf dist",
+    "build": "tsc && npm run copy -- --verbose",
+    "build:prod": "NODE_ENV=production npm run build",
+    "copy": "cp -r static dist",
+    "ver": "echo $npm_package_version",
+    "other": "npm run missing"
+  },
+  "devDependencies": { "typescript": "^5" }
+}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            task(&imported, "build").def.cmd,
+            "muu prebuild\ntsc && muu copy -- --verbose"
+        );
+        assert_eq!(
+            task(&imported, "build:prod").def.cmd,
+            "NODE_ENV=production muu build"
+        );
+        assert_eq!(task(&imported, "other").def.cmd, "npm run missing");
+        assert_eq!(imported.warnings().len(), 2);
+        let cfg = round_trip(&imported);
+        assert!(cfg.tasks.contains_key("build:prod"));
+    }
+
+    #[test]
+    fn render_multiline_and_quoting() {
+        let mut imported = Imported::default();
+        imported.push(
+            "db:seed",
+            TaskDef {
+                cmd: "echo \"a\"\necho b".to_string(),
+                args: [(
+                    "name".to_string(),
+                    ArgDef {
+                        default: String::new(),
+                        optional: true,
+                        ..Default::default()
+                    },
+                )]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            },
+            Vec::new(),
+        );
+        assert_eq!(
+            imported.to_toml(),
+            "[tasks.\"db:seed\"]\ncmd = '''\necho \"a\"\necho b\n'''\nargs = { name = \"?\" }\n"
+        );
+    }
+}
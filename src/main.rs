@@ -1,33 +1,70 @@
+mod choices;
 mod cli;
 mod commands;
 mod completions;
 mod config;
+mod defaults;
+mod docs;
 mod error;
+mod export;
+mod hash;
+mod history;
+mod import;
+mod matrix;
+mod namespace;
+mod providers;
+mod requires;
 mod runner;
+mod scripts;
 mod task;
 
+use std::ffi::OsString;
+use std::io::{self, IsTerminal};
 use std::process;
 
 use clap::Parser;
 use clap_complete::CompleteEnv;
 
-use cli::{Cli, Command};
+use cli::{Cli, Command, HistoryAction};
+use commands::run::RunOptions;
+use config::ResolvedTask;
 use error::MuuError;
 
 fn main() {
     CompleteEnv::with_factory(cli::build_cli).complete();
 
-    let cli = Cli::parse();
+    let cli = parse_cli();
     let code = match run(cli) {
         Ok(code) => code,
         Err(e) => {
             eprintln!("\x1b[31mError: {e}\x1b[0m");
-            1
+            e.exit_code()
         }
     };
     process::exit(code);
 }
 
+/// Parse the command line, running a task instead of a subcommand of the
+/// same name (see `cli::TASK_FIRST`), e.g. a `[tasks.docs]` on `muu docs`.
+fn parse_cli() -> Cli {
+    let args: Vec<OsString> = std::env::args_os().collect();
+    let words: Vec<String> = args
+        .iter()
+        .map(|a| a.to_string_lossy().into_owned())
+        .collect();
+    if let Some(i) = cli::command_index(&words)
+        && cli::TASK_FIRST.contains(&words[i].as_str())
+        && let Ok(mut cli) = Cli::try_parse_from(&args[..i])
+        && let Ok(cwd) = std::env::current_dir()
+        && let Ok(tasks) = config::load_tasks(&cwd, cli.local_only, cli.global_only)
+        && config::find_task(&tasks, &words[i]).is_some()
+    {
+        cli.command = Some(Command::External(words[i..].to_vec()));
+        return cli;
+    }
+    Cli::parse()
+}
+
 fn run(cli: Cli) -> Result<i32, MuuError> {
     let cwd = std::env::current_dir()?;
 
@@ -36,20 +73,138 @@ fn run(cli: Cli) -> Result<i32, MuuError> {
             commands::init::init(&cwd)?;
             Ok(0)
         }
+        Some(Command::Import {
+            format,
+            ref path,
+            dry_run,
+        }) => {
+            commands::import::import(&cwd, format, path.as_deref(), dry_run)?;
+            Ok(0)
+        }
+        Some(Command::Export { format, ref output }) => {
+            let tasks = config::load_tasks(&cwd, cli.local_only, cli.global_only)?;
+            commands::export::export(format, &filter_tags(tasks, &cli.tags)?, output.as_deref())?;
+            Ok(0)
+        }
+        Some(Command::Docs {
+            format,
+            ref output,
+            check,
+        }) => {
+            let tasks = config::load_tasks(&cwd, cli.local_only, cli.global_only)?;
+            let tasks = filter_tags(tasks, &cli.tags)?;
+            let root = config::project_root(&cwd).unwrap_or_else(|| cwd.clone());
+            commands::docs::docs(format, &tasks, &root, output.as_deref(), check)?;
+            Ok(0)
+        }
+        Some(Command::History {
+            action: HistoryAction::Clear { all },
+        }) => {
+            commands::history::clear(all)?;
+            Ok(0)
+        }
+        Some(Command::Show { ref task }) => {
+            let tasks = config::load_tasks(&cwd, cli.local_only, cli.global_only)?;
+            let root = config::project_root(&cwd).unwrap_or_else(|| cwd.clone());
+            commands::show::show(task, &tasks, &root)?;
+            Ok(0)
+        }
+        Some(Command::ExportScript {
+            ref task,
+            ref output,
+            shell,
+        }) => {
+            let tasks = config::load_tasks(&cwd, cli.local_only, cli.global_only)?;
+            commands::export::export_script(task, &tasks, shell, output.as_deref())?;
+            Ok(0)
+        }
         Some(Command::List) => {
             let tasks = config::load_tasks(&cwd, cli.local_only, cli.global_only)?;
-            commands::list::list(&tasks);
+            commands::list::list(&filter_tags(tasks, &cli.tags)?);
             Ok(0)
         }
+        Some(Command::Run) => {
+            if cli.tags.is_empty() {
+                return Err(MuuError::MissingTagFilter);
+            }
+            let tasks = config::load_tasks(&cwd, cli.local_only, cli.global_only)?;
+            let selected = filter_tags(tasks.clone(), &cli.tags)?;
+            commands::run::run_batch(&selected, &tasks, &run_options(&cli)?)
+        }
         Some(Command::External(ref args)) if !args.is_empty() => {
             let task_name = &args[0];
             let task_args = &args[1..];
             let tasks = config::load_tasks(&cwd, cli.local_only, cli.global_only)?;
-            commands::run::run(task_name, task_args, &tasks)
+            if commands::show::wants_help(task_name, task_args, &tasks) {
+                let root = config::project_root(&cwd).unwrap_or_else(|| cwd.clone());
+                commands::show::show(task_name, &tasks, &root)?;
+                return Ok(0);
+            }
+            if task_args.is_empty() && config::find_task(&tasks, task_name).is_none() {
+                let scoped = namespace::in_namespace(&tasks, task_name);
+                if !scoped.is_empty() {
+                    let listed = selectable(scoped, cli.all);
+                    return commands::select::select(&listed, &tasks, &run_options(&cli)?);
+                }
+            }
+            commands::run::run(task_name, task_args, &tasks, &run_options(&cli)?)
         }
-        _ => {
+        Some(Command::Select) => {
             let tasks = config::load_tasks(&cwd, cli.local_only, cli.global_only)?;
-            commands::select::select(&tasks)
+            let listed = selectable(filter_tags(tasks.clone(), &cli.tags)?, cli.all);
+            commands::select::select(&listed, &tasks, &run_options(&cli)?)
+        }
+        _ => {
+            let loaded = config::load(&cwd, cli.local_only, cli.global_only)?;
+            if let Some(ref default) = loaded.default
+                && cli.tags.is_empty()
+            {
+                return commands::run::run(default, &[], &loaded.tasks, &run_options(&cli)?);
+            }
+            let listed = selectable(filter_tags(loaded.tasks.clone(), &cli.tags)?, cli.all);
+            if !io::stdin().is_terminal() {
+                if listed.is_empty() {
+                    return Err(MuuError::NoTasksDefined);
+                }
+                commands::list::list(&listed);
+                return Ok(error::EXIT_NOT_INTERACTIVE);
+            }
+            commands::select::select(&listed, &loaded.tasks, &run_options(&cli)?)
         }
     }
 }
+
+fn run_options(cli: &Cli) -> Result<RunOptions, MuuError> {
+    Ok(RunOptions {
+        strict: cli.strict,
+        force: cli.force,
+        matrix: cli
+            .matrix
+            .iter()
+            .map(|spec| matrix::parse_spec(spec))
+            .collect::<Result<_, _>>()?,
+        parallel: cli.parallel,
+        history: !cli.no_history,
+    })
+}
+
+/// Deprecated tasks are left out of the selector unless `--all` is given.
+fn selectable(tasks: Vec<ResolvedTask>, all: bool) -> Vec<ResolvedTask> {
+    if all {
+        return tasks;
+    }
+    tasks.into_iter().filter(|t| !t.is_deprecated()).collect()
+}
+
+fn filter_tags(tasks: Vec<ResolvedTask>, tags: &[String]) -> Result<Vec<ResolvedTask>, MuuError> {
+    if tags.is_empty() {
+        return Ok(tasks);
+    }
+    let tasks: Vec<ResolvedTask> = tasks.into_iter().filter(|t| t.has_tags(tags)).collect();
+    if tasks.is_empty() {
+        return Err(MuuError::NoTaggedTasks {
+            filter: tags.join(" | "),
+        });
+    }
+    Ok(tasks)
+}
@@ -0,0 +1,193 @@
+use std::fmt;
+
+use indexmap::IndexMap;
+
+use crate::error::MuuError;
+
+/// Arg name → values to run the task with.
+pub type Matrix = IndexMap<String, Vec<String>>;
+
+/// One combination of matrix values.
+pub type Cell = IndexMap<String, String>;
+
+/// Parse a `--matrix env=dev,stg` value.
+pub fn parse_spec(spec: &str) -> Result<(String, Vec<String>), MuuError> {
+    let invalid = || MuuError::InvalidMatrix {
+        spec: spec.to_string(),
+    };
+    let (name, values) = spec.split_once('=').ok_or_else(invalid)?;
+    let values: Vec<String> = values
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect();
+    if name.is_empty() || values.is_empty() {
+        return Err(invalid());
+    }
+    Ok((name.to_string(), values))
+}
+
+/// Combine a task's matrix with `--matrix` overrides; the command line
+/// replaces the values of a key the task already defines.
+pub fn merge(task: &Matrix, cli: &[(String, Vec<String>)]) -> Matrix {
+    let mut matrix = task.clone();
+    for (name, values) in cli {
+        matrix.insert(name.clone(), values.clone());
+    }
+    matrix
+}
+
+/// Every combination of values, varying the last key fastest.
+pub fn cells(matrix: &Matrix) -> Vec<Cell> {
+    let mut cells = vec![Cell::new()];
+    for (name, values) in matrix {
+        cells = cells
+            .into_iter()
+            .flat_map(|cell| {
+                values.iter().map(move |value| {
+                    let mut cell = cell.clone();
+                    cell.insert(name.clone(), value.clone());
+                    cell
+                })
+            })
+            .collect();
+    }
+    cells
+}
+
+/// Short label for a cell, e.g. `env=dev region=us`.
+pub fn label(cell: &Cell) -> String {
+    cell.iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    UpToDate,
+    Failed(i32),
+    /// The cell couldn't run, e.g. a precondition failed; `code` is the
+    /// error's exit code.
+    Error {
+        message: String,
+        code: i32,
+    },
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Passed => write!(f, "ok"),
+            Outcome::UpToDate => write!(f, "up to date"),
+            Outcome::Failed(code) => write!(f, "failed (exit {code})"),
+            Outcome::Error { message, .. } => write!(f, "error: {message}"),
+        }
+    }
+}
+
+/// Summary table with one column per matrix key and a result column.
+pub fn render_table(matrix: &Matrix, results: &[(Cell, Outcome)]) -> String {
+    let mut header: Vec<&str> = matrix.keys().map(String::as_str).collect();
+    header.push("result");
+
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|(cell, outcome)| {
+            let mut row: Vec<String> = matrix.keys().map(|k| cell[k].clone()).collect();
+            row.push(outcome.to_string());
+            row
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain(std::iter::once(header[i].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |cols: Vec<&str>| {
+        cols.iter()
+            .zip(&widths)
+            .map(|(col, w)| format!("{col:<w$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut out = format_row(header.clone());
+    for row in &rows {
+        out.push('\n');
+        out.push_str(&format_row(row.iter().map(String::as_str).collect()));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn matrix(pairs: &[(&str, &[&str])]) -> Matrix {
+        pairs
+            .iter()
+            .map(|(k, vs)| (k.to_string(), vs.iter().map(|v| v.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_cli_spec() {
+        let (name, values) = parse_spec("env=dev, stg").unwrap();
+        assert_eq!(name, "env");
+        assert_eq!(values, vec!["dev", "stg"]);
+        assert!(matches!(
+            parse_spec("env").unwrap_err(),
+            MuuError::InvalidMatrix { .. }
+        ));
+        assert!(parse_spec("env=").is_err());
+        assert!(parse_spec("=dev").is_err());
+    }
+
+    #[test]
+    fn cartesian_product() {
+        let m = matrix(&[("env", &["dev", "stg"]), ("region", &["us", "eu"])]);
+        let labels: Vec<String> = cells(&m).iter().map(label).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "env=dev region=us",
+                "env=dev region=eu",
+                "env=stg region=us",
+                "env=stg region=eu",
+            ]
+        );
+    }
+
+    #[test]
+    fn cli_overrides_task_matrix() {
+        let task = matrix(&[("env", &["dev", "stg"]), ("region", &["us"])]);
+        let merged = merge(&task, &[("env".to_string(), vec!["prod".to_string()])]);
+        assert_eq!(merged, matrix(&[("env", &["prod"]), ("region", &["us"])]));
+    }
+
+    #[test]
+    fn table_layout() {
+        let m = matrix(&[("env", &["dev", "staging"])]);
+        let results: Vec<(Cell, Outcome)> = cells(&m)
+            .into_iter()
+            .zip([Outcome::Passed, Outcome::Failed(2)])
+            .collect();
+        assert_eq!(
+            render_table(&m, &results),
+            "env      result\ndev      ok\nstaging  failed (exit 2)"
+        );
+    }
+}
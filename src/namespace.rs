@@ -0,0 +1,151 @@
+use indexmap::IndexMap;
+
+use crate::config::ResolvedTask;
+
+pub const SEPARATOR: char = ':';
+
+/// A line in the grouped task view: either a namespace header or a task.
+pub enum Row<'a> {
+    Namespace {
+        name: &'a str,
+        depth: usize,
+    },
+    Task {
+        task: &'a ResolvedTask,
+        depth: usize,
+    },
+}
+
+/// Last segment of a namespaced name, e.g. `migrate` for `db:migrate`.
+pub fn leaf(name: &str) -> &str {
+    name.rsplit(SEPARATOR).next().unwrap_or(name)
+}
+
+/// Every namespace prefix of `name` followed by `name` itself,
+/// e.g. `a`, `a:b`, `a:b:c` for `a:b:c`.
+fn prefixes(name: &str) -> impl Iterator<Item = &str> {
+    name.match_indices(SEPARATOR)
+        .map(move |(i, _)| &name[..i])
+        .chain(std::iter::once(name))
+}
+
+/// Tasks under namespace `ns` (e.g. `db` matches `db:migrate` but not `db`).
+pub fn in_namespace(tasks: &[ResolvedTask], ns: &str) -> Vec<ResolvedTask> {
+    let prefix = format!("{ns}{SEPARATOR}");
+    tasks
+        .iter()
+        .filter(|t| t.name.starts_with(&prefix))
+        .cloned()
+        .collect()
+}
+
+/// Build the grouped view: tasks sharing a namespace are pulled together
+/// (groups keep the order in which they first appear) and each namespace
+/// gets a header row before its first task.
+pub fn tree(tasks: &[ResolvedTask]) -> Vec<Row<'_>> {
+    let mut first_seen: IndexMap<&str, usize> = IndexMap::new();
+    for task in tasks {
+        for prefix in prefixes(&task.name) {
+            let next = first_seen.len();
+            first_seen.entry(prefix).or_insert(next);
+        }
+    }
+
+    let mut ordered: Vec<&ResolvedTask> = tasks.iter().collect();
+    ordered.sort_by_cached_key(|t| {
+        prefixes(&t.name)
+            .map(|p| first_seen[p])
+            .collect::<Vec<usize>>()
+    });
+
+    let mut rows = Vec::new();
+    let mut opened: Vec<&str> = Vec::new();
+    for task in ordered {
+        let namespaces: Vec<&str> = prefixes(&task.name).collect();
+        let namespaces = &namespaces[..namespaces.len() - 1];
+        for (depth, ns) in namespaces.iter().enumerate() {
+            if !opened.contains(ns) {
+                rows.push(Row::Namespace { name: ns, depth });
+                opened.push(ns);
+            }
+        }
+        rows.push(Row::Task {
+            task,
+            depth: namespaces.len(),
+        });
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::config::{TaskDef, TaskSource};
+
+    fn tasks(names: &[&str]) -> Vec<ResolvedTask> {
+        names
+            .iter()
+            .map(|name| ResolvedTask {
+                name: name.to_string(),
+                def: TaskDef {
+                    cmd: "true".to_string(),
+                    ..Default::default()
+                },
+                source: TaskSource::Local,
+            })
+            .collect()
+    }
+
+    fn render(rows: &[Row]) -> Vec<String> {
+        rows.iter()
+            .map(|row| match row {
+                Row::Namespace { name, depth } => format!("{}{name}:", "  ".repeat(*depth)),
+                Row::Task { task, depth } => format!("{}{}", "  ".repeat(*depth), task.name),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn leaf_segment() {
+        assert_eq!(leaf("db:migrate"), "migrate");
+        assert_eq!(leaf("a:b:c"), "c");
+        assert_eq!(leaf("build"), "build");
+    }
+
+    #[test]
+    fn tree_groups_namespaces() {
+        let tasks = tasks(&["build", "db:migrate", "docker:up", "db:seed"]);
+        assert_eq!(
+            render(&tree(&tasks)),
+            vec![
+                "build",
+                "db:",
+                "  db:migrate",
+                "  db:seed",
+                "docker:",
+                "  docker:up",
+            ]
+        );
+    }
+
+    #[test]
+    fn tree_nested_namespaces() {
+        let tasks = tasks(&["a:b:c", "a:d", "a:b:e"]);
+        assert_eq!(
+            render(&tree(&tasks)),
+            vec!["a:", "  a:b:", "    a:b:c", "    a:b:e", "  a:d"]
+        );
+    }
+
+    #[test]
+    fn in_namespace_filters_by_prefix() {
+        let tasks = tasks(&["db", "db:migrate", "dbx:up", "db:seed"]);
+        let names: Vec<String> = in_namespace(&tasks, "db")
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["db:migrate", "db:seed"]);
+    }
+}
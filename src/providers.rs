@@ -0,0 +1,216 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::config::{TaskDef, TaskSource};
+use crate::task::shell_quote;
+
+/// An opt-in source of tasks defined by another tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// `scripts` in package.json, run with `npm run`.
+    Npm,
+    /// Makefile targets, run with `make`.
+    Make,
+    /// `[alias]` entries in .cargo/config.toml, run with `cargo`.
+    Cargo,
+}
+
+impl Provider {
+    pub fn source(self) -> TaskSource {
+        match self {
+            Provider::Npm => TaskSource::Npm,
+            Provider::Make => TaskSource::Make,
+            Provider::Cargo => TaskSource::Cargo,
+        }
+    }
+
+    /// Discover tasks in the project rooted at `root`, run from there
+    /// wherever muu is started. A missing or unreadable source file simply
+    /// provides no tasks.
+    pub fn discover(self, root: &Path) -> Vec<(String, TaskDef)> {
+        let read = |path: &Path| std::fs::read_to_string(root.join(path)).ok();
+        let root_arg = shell_quote(&root.display().to_string());
+        match self {
+            Provider::Npm => read(Path::new("package.json"))
+                .map(|content| npm_scripts(&content))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, script)| {
+                    // npm only hands on args after its own `--`
+                    let cmd = format!("npm --prefix {root_arg} run {} --", shell_quote(&name));
+                    (name, delegate(cmd, Some(script)))
+                })
+                .collect(),
+            Provider::Make => read(Path::new("Makefile"))
+                .map(|content| make_targets(&content))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, desc)| {
+                    let cmd = format!("make -C {root_arg} {}", shell_quote(&name));
+                    (name, delegate(cmd, desc))
+                })
+                .collect(),
+            Provider::Cargo => read(&Path::new(".cargo").join("config.toml"))
+                .map(|content| cargo_aliases(&content))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, expansion)| {
+                    let cmd = format!("cd {root_arg} && cargo {}", shell_quote(&name));
+                    (name, delegate(cmd, Some(expansion)))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A task that runs `cmd` with whatever is given after `--`, since the
+/// tool defines its own args.
+fn delegate(cmd: String, description: Option<String>) -> TaskDef {
+    TaskDef {
+        cmd,
+        description,
+        passthrough: true,
+        ..Default::default()
+    }
+}
+
+/// Script name → script body from package.json.
+fn npm_scripts(content: &str) -> Vec<(String, String)> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let Some(scripts) = json.get("scripts").and_then(|s| s.as_object()) else {
+        return Vec::new();
+    };
+    scripts
+        .iter()
+        .filter_map(|(name, body)| Some((name.clone(), body.as_str()?.to_string())))
+        .collect()
+}
+
+/// Explicit Makefile targets with their `## description`, skipping special
+/// targets (`.PHONY`), pattern rules and variable assignments.
+fn make_targets(content: &str) -> Vec<(String, Option<String>)> {
+    let mut targets: Vec<(String, Option<String>)> = Vec::new();
+    for line in content.lines() {
+        if line.starts_with(['\t', ' ', '#', '.']) {
+            continue;
+        }
+        let (head, desc) = match line.split_once("##") {
+            Some((head, desc)) => (head, Some(desc.trim().to_string())),
+            None => (line, None),
+        };
+        let Some((names, rest)) = head.split_once(':') else {
+            continue;
+        };
+        if rest.starts_with('=') || rest.starts_with(":=") || names.contains(['=', '$', '%']) {
+            continue;
+        }
+        for name in names.split_whitespace() {
+            if !targets.iter().any(|(n, _)| n == name) {
+                targets.push((name.to_string(), desc.clone()));
+            }
+        }
+    }
+    targets
+}
+
+/// Alias name → expansion from .cargo/config.toml.
+fn cargo_aliases(content: &str) -> Vec<(String, String)> {
+    let Ok(config) = toml::from_str::<toml::Table>(content) else {
+        return Vec::new();
+    };
+    let Some(aliases) = config.get("alias").and_then(|a| a.as_table()) else {
+        return Vec::new();
+    };
+    aliases
+        .iter()
+        .filter_map(|(name, value)| {
+            let expansion = match value {
+                toml::Value::String(s) => s.clone(),
+                toml::Value::Array(parts) => parts
+                    .iter()
+                    .filter_map(|p| p.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                _ => return None,
+            };
+            Some((name.clone(), expansion))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn parse_npm_scripts() {
+        let scripts = npm_scripts(
+            r#"{ "name": "app", "scripts": { "dev": "vite", "build": "vite build", "n": 1 } }"#,
+        );
+        assert_eq!(
+            scripts,
+            vec![
+                ("dev".to_string(), "vite".to_string()),
+                ("build".to_string(), "vite build".to_string()),
+            ]
+        );
+        assert!(npm_scripts("not json").is_empty());
+    }
+
+    #[test]
+    fn parse_make_targets() {
+        let targets = make_targets(
+            ".PHONY: build test\n\
+             CC := gcc\n\
+             FLAGS = -a:b\n\
+             \n\
+             build: deps ## Build everything\n\
+             \tgo build ./...\n\
+             test lint:\n\
+             \tgo test ./...\n\
+             %.o: %.c\n\
+             \t$(CC) -c $<\n",
+        );
+        assert_eq!(
+            targets,
+            vec![
+                ("build".to_string(), Some("Build everything".to_string())),
+                ("test".to_string(), None),
+                ("lint".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_cargo_aliases() {
+        let aliases = cargo_aliases(
+            r#"
+[alias]
+b = "build"
+xtask = ["run", "--package", "xtask", "--"]
+"#,
+        );
+        assert_eq!(
+            aliases,
+            vec![
+                ("b".to_string(), "build".to_string()),
+                ("xtask".to_string(), "run --package xtask --".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn discover_missing_file() {
+        let dir = TempDir::new().unwrap();
+        assert!(Provider::Npm.discover(dir.path()).is_empty());
+        assert!(Provider::Make.discover(dir.path()).is_empty());
+        assert!(Provider::Cargo.discover(dir.path()).is_empty());
+    }
+}
@@ -0,0 +1,361 @@
+use std::cmp::Ordering;
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Deserialize;
+
+use crate::error::MuuError;
+
+// ---------- Requirement definition ----------
+
+/// A tool that must be on PATH, optionally with a version constraint.
+///
+/// Written as `"node >= 18"` or as a table when the version needs a custom
+/// probe: `{ tool = "kubectl", version = "^1.28", probe = "kubectl version --client" }`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawRequirement")]
+pub struct Requirement {
+    pub tool: String,
+    pub constraint: Option<Constraint>,
+    pub probe: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawRequirement {
+    Short(String),
+    Full {
+        tool: String,
+        #[serde(default)]
+        version: Option<String>,
+        #[serde(default)]
+        probe: Option<String>,
+    },
+}
+
+impl TryFrom<RawRequirement> for Requirement {
+    type Error = String;
+
+    fn try_from(raw: RawRequirement) -> Result<Self, Self::Error> {
+        match raw {
+            RawRequirement::Short(s) => {
+                let s = s.trim();
+                let split = s.find(|c: char| c.is_whitespace() || "<>=^~".contains(c));
+                let (tool, rest) = match split {
+                    Some(i) => (&s[..i], s[i..].trim()),
+                    None => (s, ""),
+                };
+                let constraint = if rest.is_empty() {
+                    None
+                } else {
+                    Some(rest.parse()?)
+                };
+                Ok(Requirement {
+                    tool: tool.to_string(),
+                    constraint,
+                    probe: None,
+                })
+            }
+            RawRequirement::Full {
+                tool,
+                version,
+                probe,
+            } => Ok(Requirement {
+                tool,
+                constraint: version.map(|v| v.parse()).transpose()?,
+                probe,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.constraint {
+            Some(ref c) => write!(f, "{} {c}", self.tool),
+            None => write!(f, "{}", self.tool),
+        }
+    }
+}
+
+// ---------- Version constraints ----------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Ge,
+    Gt,
+    Le,
+    Lt,
+    Eq,
+    Caret,
+    Tilde,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    op: Op,
+    version: Vec<u64>,
+}
+
+impl std::str::FromStr for Constraint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (op, rest) = [
+            (">=", Op::Ge),
+            ("<=", Op::Le),
+            ("==", Op::Eq),
+            (">", Op::Gt),
+            ("<", Op::Lt),
+            ("=", Op::Eq),
+            ("^", Op::Caret),
+            ("~", Op::Tilde),
+        ]
+        .iter()
+        .find_map(|(prefix, op)| s.strip_prefix(prefix).map(|rest| (*op, rest)))
+        // A bare version behaves like Cargo's default, i.e. `^`
+        .unwrap_or((Op::Caret, s));
+
+        let version = parse_version(rest.trim())
+            .ok_or_else(|| format!("invalid version constraint '{s}'"))?;
+        Ok(Constraint { op, version })
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self.op {
+            Op::Ge => ">= ",
+            Op::Gt => "> ",
+            Op::Le => "<= ",
+            Op::Lt => "< ",
+            Op::Eq => "= ",
+            Op::Caret => "^",
+            Op::Tilde => "~",
+        };
+        write!(f, "{op}{}", format_version(&self.version))
+    }
+}
+
+impl Constraint {
+    pub fn matches(&self, found: &[u64]) -> bool {
+        let v = &self.version;
+        match self.op {
+            Op::Ge => compare(found, v) != Ordering::Less,
+            Op::Gt => compare(found, v) == Ordering::Greater,
+            Op::Le => compare(found, v) != Ordering::Greater,
+            Op::Lt => compare(found, v) == Ordering::Less,
+            Op::Eq => (0..v.len()).all(|i| found.get(i).copied().unwrap_or(0) == v[i]),
+            Op::Caret => {
+                // Bump the first non-zero component: ^1.28 → <2, ^0.3 → <0.4
+                let i = v.iter().position(|&n| n != 0).unwrap_or(v.len() - 1);
+                compare(found, v) != Ordering::Less && compare(found, &bump(v, i)) == Ordering::Less
+            }
+            Op::Tilde => {
+                // ~1 → <2, ~1.28 and ~1.28.3 → <1.29
+                let i = if v.len() == 1 { 0 } else { 1 };
+                compare(found, v) != Ordering::Less && compare(found, &bump(v, i)) == Ordering::Less
+            }
+        }
+    }
+}
+
+fn bump(v: &[u64], i: usize) -> Vec<u64> {
+    let mut upper = v[..=i].to_vec();
+    upper[i] += 1;
+    upper
+}
+
+/// Compare versions component-wise, treating missing components as 0.
+fn compare(a: &[u64], b: &[u64]) -> Ordering {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| {
+            let x = a.get(i).copied().unwrap_or(0);
+            let y = b.get(i).copied().unwrap_or(0);
+            x.cmp(&y)
+        })
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn parse_version(s: &str) -> Option<Vec<u64>> {
+    let s = s.strip_prefix('v').unwrap_or(s);
+    if s.is_empty() {
+        return None;
+    }
+    s.split('.').map(|part| part.parse().ok()).collect()
+}
+
+fn format_version(v: &[u64]) -> String {
+    v.iter().map(u64::to_string).collect::<Vec<_>>().join(".")
+}
+
+/// Pull the first version number out of probe output, preferring a dotted
+/// one (`Docker version 24.0.7, build afdd53b` → 24.0.7).
+fn extract_version(output: &str) -> Option<Vec<u64>> {
+    let runs: Vec<&str> = output
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map(|run| run.trim_matches('.'))
+        .filter(|run| run.starts_with(|c: char| c.is_ascii_digit()))
+        .collect();
+    let best = runs
+        .iter()
+        .find(|run| run.contains('.'))
+        .or_else(|| runs.first())?;
+    // Drop anything after an empty component, e.g. `1.2..3`
+    let version: Vec<u64> = best
+        .split('.')
+        .map_while(|part| part.parse().ok())
+        .collect();
+    (!version.is_empty()).then_some(version)
+}
+
+// ---------- Checking ----------
+
+/// Check every requirement and report all failures together.
+pub fn check(requirements: &[Requirement]) -> Result<(), MuuError> {
+    let failures: Vec<String> = requirements.iter().filter_map(check_one).collect();
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(MuuError::RequirementsNotMet { failures })
+    }
+}
+
+fn check_one(req: &Requirement) -> Option<String> {
+    if find_on_path(&req.tool).is_none() {
+        return Some(format!("{req}: '{}' not found on PATH", req.tool));
+    }
+    let constraint = req.constraint.as_ref()?;
+    let Some(found) = probe_version(req) else {
+        return Some(format!("{req}: could not determine version"));
+    };
+    if constraint.matches(&found) {
+        None
+    } else {
+        Some(format!("{req}: found {}", format_version(&found)))
+    }
+}
+
+fn probe_version(req: &Requirement) -> Option<Vec<u64>> {
+    let output = match req.probe {
+        Some(ref probe) => Command::new("zsh").arg("-c").arg(probe).output(),
+        None => Command::new(&req.tool).arg("--version").output(),
+    }
+    .ok()?;
+    // Some tools print their version on stderr
+    let text = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    extract_version(&text)
+}
+
+fn find_on_path(tool: &str) -> Option<PathBuf> {
+    if tool.contains(std::path::MAIN_SEPARATOR) {
+        let path = Path::new(tool);
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(tool))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn req(s: &str) -> Requirement {
+        Requirement::try_from(RawRequirement::Short(s.to_string())).unwrap()
+    }
+
+    fn c(s: &str) -> Constraint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_short_form() {
+        let r = req("node >= 18");
+        assert_eq!(r.tool, "node");
+        assert_eq!(r.constraint, Some(c(">=18")));
+        assert_eq!(r.to_string(), "node >= 18");
+
+        let r = req("kubectl ^1.28");
+        assert_eq!(r.tool, "kubectl");
+        assert_eq!(r.to_string(), "kubectl ^1.28");
+
+        let r = req("docker");
+        assert_eq!(r.tool, "docker");
+        assert!(r.constraint.is_none());
+
+        let r = req("python3>=3.10");
+        assert_eq!(r.tool, "python3");
+        assert_eq!(r.constraint, Some(c(">= 3.10")));
+    }
+
+    #[test]
+    fn parse_invalid_constraint() {
+        let err = Requirement::try_from(RawRequirement::Short("node >= abc".to_string()));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn comparison_operators() {
+        assert!(c(">= 18").matches(&[18, 0, 0]));
+        assert!(c(">= 18").matches(&[20, 1]));
+        assert!(!c(">= 18").matches(&[16, 20, 2]));
+        assert!(!c("> 18").matches(&[18]));
+        assert!(c("< 2").matches(&[1, 9]));
+        assert!(c("<= 1.2").matches(&[1, 2, 0]));
+        assert!(c("= 1.2").matches(&[1, 2, 7]));
+        assert!(!c("= 1.2").matches(&[1, 3]));
+    }
+
+    #[test]
+    fn caret_and_tilde() {
+        assert!(c("^1.28").matches(&[1, 28, 2]));
+        assert!(c("^1.28").matches(&[1, 30]));
+        assert!(!c("^1.28").matches(&[2, 0]));
+        assert!(!c("^1.28").matches(&[1, 27]));
+        assert!(c("^0.3").matches(&[0, 3, 9]));
+        assert!(!c("^0.3").matches(&[0, 4]));
+        assert!(c("~1.28").matches(&[1, 28, 9]));
+        assert!(!c("~1.28").matches(&[1, 29]));
+        assert!(c("18").matches(&[18, 5]));
+        assert!(!c("18").matches(&[19]));
+    }
+
+    #[test]
+    fn extract_from_probe_output() {
+        assert_eq!(extract_version("v18.17.1\n"), Some(vec![18, 17, 1]));
+        assert_eq!(
+            extract_version("Docker version 24.0.7, build afdd53b"),
+            Some(vec![24, 0, 7])
+        );
+        assert_eq!(
+            extract_version("zsh 5.9 (x86_64-apple-darwin23.0)"),
+            Some(vec![5, 9])
+        );
+        assert_eq!(extract_version("jq-1.7.1"), Some(vec![1, 7, 1]));
+        assert_eq!(extract_version("no digits here"), None);
+    }
+
+    #[test]
+    fn missing_tool_reported() {
+        let err = check(&[req("muu-definitely-missing-tool"), req("sh")]).unwrap_err();
+        match err {
+            MuuError::RequirementsNotMet { failures } => {
+                assert_eq!(failures.len(), 1);
+                assert!(failures[0].contains("not found on PATH"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+}
@@ -1,24 +1,135 @@
-use std::process::Command;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
-/// Execute a command string via `zsh -c` with `set -e` prepended.
-/// Returns the exit code (0 on success).
-pub fn execute(cmd: &str) -> i32 {
-    let script = format!("set -e\n{cmd}");
-    let status = Command::new("zsh").arg("-c").arg(&script).status();
+/// Build a `zsh -c` invocation of `cmd` with `set -e` prepended, so
+/// multi-line commands stop at the first failing line.
+pub fn shell(cmd: &str) -> Command {
+    let mut command = Command::new("zsh");
+    command.arg("-c").arg(format!("set -e\n{cmd}"));
+    command
+}
+
+/// Build an invocation that runs an executable file directly.
+pub fn file(path: &Path, args: &[String]) -> Command {
+    let mut command = Command::new(path);
+    command.args(args);
+    command
+}
 
-    match status {
+/// Run a command to completion and return its exit code (0 on success).
+pub fn status(mut command: Command) -> i32 {
+    match command.status() {
         Ok(s) => s.code().unwrap_or(1),
         Err(e) => {
-            eprintln!("error: failed to execute zsh: {e}");
+            eprintln!("error: failed to execute {}: {e}", program_name(&command));
             1
         }
     }
 }
 
+fn program_name(command: &Command) -> String {
+    command.get_program().to_string_lossy().into_owned()
+}
+
+/// Like [`status`], but prefix every output line with `[label]` so the
+/// output of concurrent runs can be told apart.
+pub fn status_labeled(mut command: Command, label: &str) -> i32 {
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("error: failed to execute {}: {e}", program_name(&command));
+            return 1;
+        }
+    };
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    thread::scope(|s| {
+        if let Some(out) = stdout {
+            s.spawn(move || {
+                for line in BufReader::new(out).lines().map_while(Result::ok) {
+                    println!("[{label}] {line}");
+                }
+            });
+        }
+        if let Some(err) = stderr {
+            s.spawn(move || {
+                for line in BufReader::new(err).lines().map_while(Result::ok) {
+                    eprintln!("[{label}] {line}");
+                }
+            });
+        }
+    });
+
+    match child.wait() {
+        Ok(s) => s.code().unwrap_or(1),
+        Err(_) => 1,
+    }
+}
+
+/// Run a check command quietly via `zsh -c` and report whether it exited 0.
+pub fn succeeds(cmd: &str) -> bool {
+    Command::new("zsh")
+        .arg("-c")
+        .arg(cmd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Run `cmd` via `zsh -c` and capture its stdout. Gives up and kills it
+/// after `timeout`; `None` if it fails, times out or can't be started.
+pub fn output(cmd: &str, timeout: Duration) -> Option<String> {
+    let mut child = Command::new("zsh")
+        .arg("-c")
+        .arg(cmd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    // Read on another thread so a full pipe can't block the child
+    let mut stdout = child.stdout.take()?;
+    let reader = thread::spawn(move || {
+        let mut out = String::new();
+        stdout.read_to_string(&mut out).map(|_| out)
+    });
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                let out = reader.join().ok()?.ok()?;
+                return status.success().then_some(out);
+            }
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn execute(cmd: &str) -> i32 {
+        status(shell(cmd))
+    }
+
     #[test]
     fn success() {
         assert_eq!(execute("true"), 0);
@@ -36,6 +147,39 @@ mod tests {
         assert_ne!(code, 0);
     }
 
+    #[test]
+    fn succeeds_reports_exit_status() {
+        assert!(succeeds("true"));
+        assert!(!succeeds("false"));
+        assert!(!succeeds("echo noisy; exit 4"));
+    }
+
+    #[test]
+    fn labeled_exit_code() {
+        assert_eq!(status_labeled(shell("echo hi"), "x"), 0);
+        assert_eq!(status_labeled(shell("echo hi\nexit 7"), "x"), 7);
+    }
+
+    #[test]
+    fn file_passes_args() {
+        let code = status(file(
+            Path::new("sh"),
+            &["-c".to_string(), "exit 5".to_string()],
+        ));
+        assert_eq!(code, 5);
+    }
+
+    #[test]
+    fn output_captures_stdout_within_timeout() {
+        let timeout = Duration::from_secs(5);
+        assert_eq!(output("echo a; echo b", timeout).as_deref(), Some("a\nb\n"));
+        assert_eq!(output("echo a; exit 1", timeout), None);
+
+        let start = Instant::now();
+        assert_eq!(output("sleep 5", Duration::from_millis(100)), None);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
     #[test]
     fn multiline_success() {
         assert_eq!(execute("echo a\necho b\ntrue"), 0);
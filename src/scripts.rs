@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+
+use crate::config::{self, ArgDef, TaskDef};
+use crate::error::MuuError;
+
+/// Turn every executable in `dir` into a task named after the file (without
+/// its extension). Description and args come from the header comment:
+///
+/// ```sh
+/// #!/bin/sh
+/// # muu:description Deploy the site
+/// # muu:arg dir=.
+/// # muu:arg bucket
+/// ```
+pub fn discover(dir: &Path) -> Result<Vec<(String, TaskDef)>, MuuError> {
+    let entries = std::fs::read_dir(dir).map_err(|_| MuuError::ScriptsDirNotFound {
+        path: dir.to_path_buf(),
+    })?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| is_executable(path))
+        .collect();
+    paths.sort();
+
+    let mut tasks: Vec<(String, TaskDef)> = Vec::new();
+    for path in paths {
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        if tasks.iter().any(|(n, _)| n == name) {
+            return Err(MuuError::DuplicateTask {
+                name: name.to_string(),
+                path: dir.to_path_buf(),
+            });
+        }
+
+        // Binaries have no readable header; they still become tasks
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        let (description, args) =
+            parse_header(&content).map_err(|reason| MuuError::ConfigParse {
+                path: path.clone(),
+                reason,
+            })?;
+        tasks.push((
+            name.to_string(),
+            TaskDef {
+                description,
+                args,
+                script: Some(path),
+                ..Default::default()
+            },
+        ));
+    }
+    Ok(tasks)
+}
+
+/// Read `muu:` directives from the leading comment block of a script.
+fn parse_header(content: &str) -> Result<(Option<String>, IndexMap<String, ArgDef>), String> {
+    let mut description = None;
+    let mut args = IndexMap::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("#!") {
+            continue;
+        }
+        let Some(comment) = line.strip_prefix('#') else {
+            break;
+        };
+        let comment = comment.trim_start_matches('#').trim();
+        if let Some(desc) = comment.strip_prefix("muu:description") {
+            description = Some(desc.trim().to_string());
+        } else if let Some(arg) = comment.strip_prefix("muu:arg") {
+            let (name, default) = arg.trim().split_once('=').unwrap_or((arg.trim(), ""));
+            if !name.is_empty() {
+                args.insert(name.to_string(), config::shorthand_arg(default.to_string()));
+            }
+        }
+    }
+    config::find_templates(&mut args)?;
+    Ok((description, args))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn header_directives() {
+        let (desc, args) = parse_header(
+            "#!/usr/bin/env python3\n\
+             # muu:description Deploy the site\n\
+             # muu:arg dir=.\n\
+             # muu:arg bucket\n\
+             # muu:arg name=?\n\
+             import sys\n\
+             # muu:arg ignored=1\n",
+        )
+        .unwrap();
+        assert_eq!(desc.as_deref(), Some("Deploy the site"));
+        let keys: Vec<&String> = args.keys().collect();
+        assert_eq!(keys, vec!["dir", "bucket", "name"]);
+        assert_eq!(args["dir"].default, ".");
+        assert_eq!(args["bucket"].default, "");
+        assert!(!args["bucket"].optional);
+        assert!(args["name"].optional);
+
+        let err = parse_header("# muu:arg env=dev\n# muu:arg bucket={{ evn }}-site\n").unwrap_err();
+        assert!(err.contains("unknown arg `evn`"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn discover_only_executables() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let write = |name: &str, mode: u32| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "#!/bin/sh\n# muu:description hi\necho hi\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        };
+        write("deploy.sh", 0o755);
+        write("notes.txt", 0o644);
+        write(".hidden", 0o755);
+
+        let tasks = discover(dir.path()).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].0, "deploy");
+        assert_eq!(tasks[0].1.description.as_deref(), Some("hi"));
+        assert_eq!(tasks[0].1.script, Some(dir.path().join("deploy.sh")));
+    }
+
+    #[test]
+    fn missing_dir() {
+        let dir = TempDir::new().unwrap();
+        let err = discover(&dir.path().join("nope")).unwrap_err();
+        assert!(matches!(err, MuuError::ScriptsDirNotFound { .. }));
+    }
+}
@@ -1,102 +1,388 @@
 use indexmap::IndexMap;
 
-use crate::config::ArgDef;
+use std::path::Path;
+
+use crate::config::{ArgDef, ArgType, TaskDef};
+use crate::defaults;
 use crate::error::MuuError;
 
-/// Classify raw CLI args as positional or named.
-enum ArgStyle {
-    Positional(Vec<String>),
-    Named(Vec<(String, String)>),
-    None,
+/// Key in the resolved args for everything after `--`, as shell-quoted
+/// words. It expands as `$@`, and also as `$MUU_ARGS`.
+pub const PASSTHROUGH: &str = "@";
+
+/// Raw CLI args sorted into named values, positional values and the
+/// pass-through rest after `--`.
+#[derive(Debug, Default)]
+struct ParsedArgs {
+    named: Vec<(String, String)>,
+    positional: Vec<String>,
+    passthrough: Option<Vec<String>>,
 }
 
-fn classify_args(raw: &[String]) -> Result<ArgStyle, MuuError> {
-    if raw.is_empty() {
-        return Ok(ArgStyle::None);
+/// Sort raw CLI args. Understands `--name=value`, `--name value`, short
+/// aliases (`-b value`, `-b=value`), make-style `name=value` for defined
+/// args, and `--`. In strict mode only `--name=value` is accepted, a bare
+/// `--name` is an empty value, and positional and named args can't be mixed.
+fn classify_args(
+    defined: &IndexMap<String, ArgDef>,
+    raw: &[String],
+    strict: bool,
+) -> Result<ParsedArgs, MuuError> {
+    let (raw, passthrough) = match raw.iter().position(|a| a == "--") {
+        Some(i) => (&raw[..i], Some(raw[i + 1..].to_vec())),
+        None => (raw, None),
+    };
+    let mut parsed = ParsedArgs {
+        passthrough,
+        ..Default::default()
+    };
+
+    if strict {
+        for arg in raw {
+            match arg.strip_prefix("--") {
+                Some(stripped) => {
+                    let pair = match stripped.split_once('=') {
+                        Some((key, value)) => (key.to_string(), value.to_string()),
+                        None => bare_flag(defined, stripped)
+                            .unwrap_or_else(|| (stripped.to_string(), String::new())),
+                    };
+                    parsed.named.push(pair);
+                }
+                None => parsed.positional.push(arg.clone()),
+            }
+        }
+        if !parsed.named.is_empty() && !parsed.positional.is_empty() {
+            return Err(MuuError::MixedArgStyles);
+        }
+        return Ok(parsed);
+    }
+
+    let mut tokens = raw.iter();
+    while let Some(arg) = tokens.next() {
+        let (name, inline) = if let Some(stripped) = arg.strip_prefix("--") {
+            match stripped.split_once('=') {
+                Some((key, value)) => (key.to_string(), Some(value)),
+                None => (stripped.to_string(), None),
+            }
+        } else if let Some(short) = short_alias(arg) {
+            let (flag, value) = match short.split_once('=') {
+                Some((flag, value)) => (flag, Some(value)),
+                None => (short, None),
+            };
+            let name = defined
+                .iter()
+                .find(|(_, a)| a.short.is_some_and(|c| flag == c.to_string()))
+                .map(|(name, _)| name.clone())
+                .ok_or_else(|| MuuError::UnknownArg {
+                    name: format!("-{flag}"),
+                })?;
+            (name, value)
+        } else if let Some((key, value)) = arg.split_once('=')
+            && defined.contains_key(key)
+        {
+            (key.to_string(), Some(value))
+        } else {
+            parsed.positional.push(arg.clone());
+            continue;
+        };
+
+        if inline.is_none()
+            && let Some(pair) = bare_flag(defined, &name)
+        {
+            parsed.named.push(pair);
+            continue;
+        }
+        if !defined.contains_key(&name) {
+            return Err(MuuError::UnknownArg { name });
+        }
+        let value = match inline {
+            Some(value) => value.to_string(),
+            None => match tokens.next() {
+                Some(next) if !next.starts_with("--") => next.clone(),
+                _ => return Err(MuuError::MissingArgValue { name }),
+            },
+        };
+        parsed.named.push((name, value));
     }
+    Ok(parsed)
+}
 
-    let has_named = raw.iter().any(|a| a.starts_with("--"));
-    let has_positional = raw.iter().any(|a| !a.starts_with("--"));
+fn is_flag(defined: &IndexMap<String, ArgDef>, name: &str) -> bool {
+    defined.get(name).is_some_and(|a| a.kind == ArgType::Flag)
+}
 
-    if has_named && has_positional {
-        return Err(MuuError::MixedArgStyles);
+/// `--name` sets a flag arg and `--no-name` clears it.
+fn bare_flag(defined: &IndexMap<String, ArgDef>, name: &str) -> Option<(String, String)> {
+    if is_flag(defined, name) {
+        return Some((name.to_string(), "true".to_string()));
     }
+    let negated = name.strip_prefix("no-")?;
+    is_flag(defined, negated).then(|| (negated.to_string(), "false".to_string()))
+}
 
-    if has_named {
-        let mut pairs = Vec::new();
-        for arg in raw {
-            let stripped = arg.strip_prefix("--").unwrap();
-            if let Some((key, value)) = stripped.split_once('=') {
-                pairs.push((key.to_string(), value.to_string()));
-            } else {
-                pairs.push((stripped.to_string(), String::new()));
-            }
-        }
-        Ok(ArgStyle::Named(pairs))
+/// The flag part of `-b`, `-b=value` or `-bvalue`-style tokens. Negative
+/// numbers and a lone `-` are values, not flags.
+fn short_alias(arg: &str) -> Option<&str> {
+    let rest = arg.strip_prefix('-')?;
+    if rest.is_empty() || rest.starts_with('-') || arg.parse::<f64>().is_ok() {
+        return None;
+    }
+    Some(rest)
+}
+
+/// Whether `cmd` places the pass-through args itself.
+pub fn uses_passthrough(cmd: &str) -> bool {
+    cmd.contains("$@") || cmd.contains("$MUU_ARGS")
+}
+
+/// Whether a task accepts args after `--`.
+pub fn takes_passthrough(def: &TaskDef) -> bool {
+    def.passthrough || def.script.is_some() || uses_passthrough(&def.cmd)
+}
+
+/// The task's `cmd`, with `$@` appended when `passthrough` is set and the
+/// command doesn't place the args itself.
+pub fn command_with_passthrough(def: &TaskDef) -> String {
+    if def.passthrough && !uses_passthrough(&def.cmd) {
+        format!("{} $@", def.cmd.trim_end())
     } else {
-        Ok(ArgStyle::Positional(raw.to_vec()))
+        def.cmd.clone()
     }
 }
 
 /// Resolve raw args against the defined args for a task.
-/// Returns a map of arg name → resolved value.
+/// Returns a map of arg name → resolved value. Named args claim their
+/// slots first; positional values fill the remaining ones in order. A
+/// variadic arg collects the positional values left over (and every time
+/// it is named) and resolves to them as a shell-quoted word list. Args
+/// after `--` end up the same way under [`PASSTHROUGH`].
 pub fn resolve_args(
     defined: &IndexMap<String, ArgDef>,
     raw: &[String],
 ) -> Result<IndexMap<String, String>, MuuError> {
-    let style = classify_args(raw)?;
-    let mut resolved: IndexMap<String, String> = IndexMap::new();
+    resolve(defined, raw, false, &IndexMap::new())
+}
 
-    match style {
-        ArgStyle::None => {
-            for (name, arg) in defined {
-                if arg.default.is_empty() && !arg.optional {
-                    return Err(MuuError::MissingRequiredArg {
-                        name: name.clone(),
-                    });
-                }
-                resolved.insert(name.clone(), arg.default.clone());
-            }
+/// Like [`resolve_args`], with the stricter syntax of `strict_args`:
+/// either all positional or all `--name=value`, and the last value for a
+/// name wins.
+pub fn resolve_args_strict(
+    defined: &IndexMap<String, ArgDef>,
+    raw: &[String],
+) -> Result<IndexMap<String, String>, MuuError> {
+    resolve(defined, raw, true, &IndexMap::new())
+}
+
+/// Resolve args for one matrix cell: the cell's args take no positional
+/// slot and can't be given, and their values are checked like given ones.
+pub fn resolve_cell_args(
+    defined: &IndexMap<String, ArgDef>,
+    raw: &[String],
+    strict: bool,
+    cell: &IndexMap<String, String>,
+) -> Result<IndexMap<String, String>, MuuError> {
+    resolve(defined, raw, strict, cell)
+}
+
+fn resolve(
+    defined: &IndexMap<String, ArgDef>,
+    raw: &[String],
+    strict: bool,
+    cell: &IndexMap<String, String>,
+) -> Result<IndexMap<String, String>, MuuError> {
+    let parsed = classify_args(defined, raw, strict)?;
+    let mut given: IndexMap<&str, Vec<String>> = IndexMap::new();
+
+    for (key, value) in parsed.named {
+        let Some((name, arg)) = defined.get_key_value(&key) else {
+            return Err(MuuError::UnknownArg { name: key });
+        };
+        if cell.contains_key(name) {
+            return Err(MuuError::MatrixArgGiven { name: key });
         }
-        ArgStyle::Positional(values) => {
-            for (i, (name, arg)) in defined.iter().enumerate() {
-                if let Some(val) = values.get(i) {
-                    resolved.insert(name.clone(), val.clone());
-                } else if arg.default.is_empty() && !arg.optional {
-                    return Err(MuuError::MissingRequiredArg {
-                        name: name.clone(),
-                    });
-                } else {
-                    resolved.insert(name.clone(), arg.default.clone());
-                }
+        let values = given.entry(name).or_default();
+        if !arg.variadic && !values.is_empty() {
+            if !strict {
+                return Err(MuuError::DuplicateArg { name: key });
             }
+            values.clear();
+        }
+        values.push(value);
+    }
+
+    // Flags are only ever named, so they take no positional slot
+    let takes_slot =
+        |name: &str, arg: &ArgDef| arg.kind != ArgType::Flag && !cell.contains_key(name);
+    let mut positional = parsed.positional.into_iter();
+    for (name, arg) in defined.iter().filter(|(n, a)| takes_slot(n, a)) {
+        if arg.variadic {
+            given.entry(name).or_default().extend(positional.by_ref());
+            break;
+        }
+        if given.contains_key(name.as_str()) {
+            continue;
+        }
+        match positional.next() {
+            Some(value) => given.insert(name, vec![value]),
+            None => break,
+        };
+    }
+    let extra = positional.count();
+    if extra > 0 {
+        let slots = defined.iter().filter(|(n, a)| takes_slot(n, a)).count();
+        return Err(MuuError::TooManyArgs {
+            expected: slots,
+            got: slots + extra,
+        });
+    }
+
+    let mut resolved: IndexMap<String, String> = IndexMap::new();
+    let mut pending = Vec::new();
+    for (name, arg) in defined {
+        if let Some(value) = cell.get(name) {
+            resolved.insert(name.clone(), resolve_value(name, arg, value)?);
+            continue;
+        }
+        let values = given.swap_remove(name.as_str());
+        if values.is_none() && arg.computed.is_some() && arg.env_value().is_none() {
+            pending.push(name.as_str());
+            continue;
         }
-        ArgStyle::Named(pairs) => {
-            // Start with defaults
-            for (name, arg) in defined {
-                resolved.insert(name.clone(), arg.default.clone());
+        let value = if arg.variadic {
+            let values = values
+                .filter(|v| !v.is_empty())
+                .or_else(|| arg.env_value().and_then(|v| split_words(&v)))
+                .unwrap_or_default();
+            if values.len() < arg.min_count {
+                return Err(MuuError::TooFewValues {
+                    name: name.clone(),
+                    min: arg.min_count,
+                    got: values.len(),
+                });
             }
-            // Override with provided values
-            for (key, value) in &pairs {
-                if resolved.contains_key(key) {
-                    resolved.insert(key.clone(), value.clone());
-                } else {
-                    return Err(MuuError::UnknownArg { name: key.clone() });
+            let checked = values
+                .iter()
+                .map(|v| check_value(name, arg, v).map(|v| shell_quote(&v)))
+                .collect::<Result<Vec<_>, _>>()?;
+            checked.join(" ")
+        } else if arg.kind == ArgType::Flag {
+            let value = values
+                .and_then(|mut v| v.pop())
+                .or_else(|| arg.env_value())
+                .unwrap_or_else(|| arg.default.clone());
+            flag_value(name, arg, &value)?
+        } else {
+            let value = values
+                .and_then(|mut v| v.pop())
+                .or_else(|| arg.env_value())
+                .unwrap_or_else(|| arg.default.clone());
+            check_resolved(name, arg, value)?
+        };
+        resolved.insert(name.clone(), value);
+    }
+    defaults::compute(defined, &pending, &mut resolved)?;
+    if let Some(words) = parsed.passthrough {
+        let quoted: Vec<String> = words.iter().map(|w| shell_quote(w)).collect();
+        resolved.insert(PASSTHROUGH.to_string(), quoted.join(" "));
+    }
+
+    Ok(resolved)
+}
+
+/// What a flag given as `value` expands to; empty means not set.
+fn flag_value(name: &str, arg: &ArgDef, value: &str) -> Result<String, MuuError> {
+    let set = !value.is_empty() && check_value(name, arg, value)? == "true";
+    Ok(arg.flag_text(set))
+}
+
+/// Resolve `value` as the only value given for an arg: checked, a flag's
+/// on/off text, or a one-word list for a variadic arg.
+pub fn resolve_value(name: &str, arg: &ArgDef, value: &str) -> Result<String, MuuError> {
+    if arg.variadic {
+        Ok(shell_quote(&check_value(name, arg, value)?))
+    } else if arg.kind == ArgType::Flag {
+        flag_value(name, arg, value)
+    } else {
+        check_resolved(name, arg, value.to_string())
+    }
+}
+
+/// Check the value an arg ends up with; empty is only fine if it's optional.
+pub fn check_resolved(name: &str, arg: &ArgDef, value: String) -> Result<String, MuuError> {
+    if !value.is_empty() {
+        check_value(name, arg, &value)
+    } else if arg.optional {
+        Ok(value)
+    } else {
+        Err(MuuError::MissingRequiredArg {
+            name: name.to_string(),
+        })
+    }
+}
+
+/// Check a value against the arg's type, bounds and choices. Returns the
+/// value to use, which for bools is normalized to `true` or `false`.
+pub fn check_value(name: &str, arg: &ArgDef, value: &str) -> Result<String, MuuError> {
+    let invalid = |expected: &str| MuuError::InvalidArgValue {
+        name: name.to_string(),
+        value: value.to_string(),
+        expected: expected.to_string(),
+    };
+    match arg.kind {
+        ArgType::String | ArgType::Enum => {}
+        ArgType::Int | ArgType::Float => {
+            let number = if arg.kind == ArgType::Int {
+                value
+                    .parse::<i64>()
+                    .map(|i| i as f64)
+                    .map_err(|_| invalid("an integer"))?
+            } else {
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|f| f.is_finite())
+                    .ok_or_else(|| invalid("a number"))?
+            };
+            let bound = match (arg.min, arg.max) {
+                (Some(min), Some(max)) if number < min || number > max => {
+                    Some(format!("between {min} and {max}"))
                 }
+                (Some(min), None) if number < min => Some(format!("at least {min}")),
+                (None, Some(max)) if number > max => Some(format!("at most {max}")),
+                _ => None,
+            };
+            if let Some(bound) = bound {
+                return Err(MuuError::ArgOutOfRange {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                    bound,
+                });
             }
-            // Check required args
-            for (name, value) in &resolved {
-                let arg = &defined[name];
-                if value.is_empty() && !arg.optional {
-                    return Err(MuuError::MissingRequiredArg {
-                        name: name.clone(),
-                    });
-                }
+        }
+        ArgType::Bool | ArgType::Flag => {
+            return match value.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Ok("true".to_string()),
+                "false" | "no" | "off" | "0" => Ok("false".to_string()),
+                _ => Err(invalid("true or false")),
+            };
+        }
+        ArgType::Path => {
+            if arg.must_exist && !Path::new(value).exists() {
+                return Err(MuuError::ArgPathNotFound {
+                    name: name.to_string(),
+                    path: value.into(),
+                });
             }
         }
     }
-
-    Ok(resolved)
+    if !arg.choices.is_empty() && !arg.choices.iter().any(|c| c == value) {
+        return Err(MuuError::InvalidChoice {
+            name: name.to_string(),
+            value: value.to_string(),
+            choices: arg.choices.clone(),
+        });
+    }
+    Ok(value.to_string())
 }
 
 /// Expand `$name` placeholders in a command string.
@@ -107,6 +393,9 @@ pub fn expand_command(cmd: &str, resolved: &IndexMap<String, String>) -> String
     keys.sort_by_key(|k| std::cmp::Reverse(k.len()));
 
     let mut result = cmd.to_string();
+    if let Some(rest) = resolved.get(PASSTHROUGH) {
+        result = result.replace("$MUU_ARGS", rest);
+    }
     for key in keys {
         let placeholder = format!("${key}");
         result = result.replace(&placeholder, &resolved[key]);
@@ -114,6 +403,53 @@ pub fn expand_command(cmd: &str, resolved: &IndexMap<String, String>) -> String
     result
 }
 
+/// Quote a value for safe use as a single shell word.
+pub fn shell_quote(s: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c);
+    if !s.is_empty() && s.chars().all(safe) {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
+/// Split shell-style words, the inverse of joining [`shell_quote`]d values.
+/// Handles single and double quotes and backslash escapes; `None` if a
+/// quote is left open.
+pub fn split_words(s: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let w = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => w.push(c),
+                    }
+                }
+            }
+            '"' => {
+                let w = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => w.push(chars.next()?),
+                        c => w.push(c),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_with(String::new).push(chars.next()?),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Some(words)
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -129,6 +465,7 @@ mod tests {
                     ArgDef {
                         default: v.to_string(),
                         optional: false,
+                        ..Default::default()
                     },
                 )
             })
@@ -144,6 +481,7 @@ mod tests {
                     ArgDef {
                         default: v.to_string(),
                         optional: *opt,
+                        ..Default::default()
                     },
                 )
             })
@@ -180,8 +518,7 @@ mod tests {
     #[test]
     fn named_args() {
         let defined = idx(&[("dir", "."), ("bucket", "")]);
-        let resolved =
-            resolve_args(&defined, &strs(&["--bucket=my-bucket"])).unwrap();
+        let resolved = resolve_args(&defined, &strs(&["--bucket=my-bucket"])).unwrap();
         assert_eq!(resolved["dir"], ".");
         assert_eq!(resolved["bucket"], "my-bucket");
     }
@@ -204,10 +541,145 @@ mod tests {
     fn mixed_args_error() {
         let defined = idx(&[("dir", "."), ("bucket", "")]);
         let err =
-            resolve_args(&defined, &strs(&["./dist", "--bucket=my-bucket"])).unwrap_err();
+            resolve_args_strict(&defined, &strs(&["./dist", "--bucket=my-bucket"])).unwrap_err();
         assert!(matches!(err, MuuError::MixedArgStyles));
     }
 
+    #[test]
+    fn strict_keeps_bare_names_and_last_value() {
+        let defined = idx_opt(&[("dir", ".", false), ("name", "", true)]);
+        let resolved =
+            resolve_args_strict(&defined, &strs(&["--name", "--dir=a", "--dir=b"])).unwrap();
+        assert_eq!(resolved["dir"], "b");
+        assert_eq!(resolved["name"], "");
+        // No make-style names either
+        let resolved = resolve_args_strict(&defined, &strs(&["name=x"])).unwrap();
+        assert_eq!(resolved["dir"], "name=x");
+    }
+
+    #[test]
+    fn mixed_args_named_claim_slots_first() {
+        let defined = idx(&[("dir", "."), ("bucket", ""), ("tag", "latest")]);
+        let resolved =
+            resolve_args(&defined, &strs(&["--bucket=my-bucket", "./dist", "v2"])).unwrap();
+        assert_eq!(resolved["dir"], "./dist");
+        assert_eq!(resolved["bucket"], "my-bucket");
+        assert_eq!(resolved["tag"], "v2");
+    }
+
+    #[test]
+    fn named_value_after_space_and_make_style() {
+        let defined = idx(&[("dir", "."), ("bucket", "")]);
+        let resolved = resolve_args(&defined, &strs(&["--bucket", "b", "dir=./dist"])).unwrap();
+        assert_eq!(resolved["dir"], "./dist");
+        assert_eq!(resolved["bucket"], "b");
+
+        // `key=value` for an unknown key is an ordinary positional value
+        let resolved = resolve_args(&defined, &strs(&["--bucket=b", "FOO=1"])).unwrap();
+        assert_eq!(resolved["dir"], "FOO=1");
+    }
+
+    #[test]
+    fn short_aliases() {
+        let mut defined = idx(&[("dir", ".")]);
+        defined.insert(
+            "bucket".to_string(),
+            ArgDef {
+                short: Some('b'),
+                ..Default::default()
+            },
+        );
+        let resolved = resolve_args(&defined, &strs(&["-b", "x", "src"])).unwrap();
+        assert_eq!(
+            (resolved["dir"].as_str(), resolved["bucket"].as_str()),
+            ("src", "x")
+        );
+        let resolved = resolve_args(&defined, &strs(&["-b=y"])).unwrap();
+        assert_eq!(resolved["bucket"], "y");
+
+        let err = resolve_args(&defined, &strs(&["-x", "1"])).unwrap_err();
+        assert_eq!(err.to_string(), "unknown argument '-x'");
+    }
+
+    fn flag(on: &str, off: &str) -> ArgDef {
+        ArgDef {
+            default: "false".to_string(),
+            optional: true,
+            kind: ArgType::Flag,
+            on: Some(on.to_string()),
+            off: Some(off.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn flags_are_named_only() {
+        let mut defined = idx(&[("target", "debug")]);
+        defined.insert("force".to_string(), flag("--force", ""));
+        defined.insert(
+            "color".to_string(),
+            ArgDef {
+                default: "true".to_string(),
+                short: Some('c'),
+                ..flag("always", "never")
+            },
+        );
+
+        let resolved = resolve_args(&defined, &strs(&["release", "--force"])).unwrap();
+        assert_eq!(resolved["target"], "release");
+        assert_eq!(resolved["force"], "--force");
+        assert_eq!(resolved["color"], "always");
+
+        let resolved = resolve_args(&defined, &strs(&["--no-color", "--force=no"])).unwrap();
+        assert_eq!(resolved["target"], "debug");
+        assert_eq!(resolved["force"], "");
+        assert_eq!(resolved["color"], "never");
+
+        let resolved = resolve_args(&defined, &strs(&["-c", "release"])).unwrap();
+        assert_eq!(resolved["color"], "always");
+        assert_eq!(resolved["target"], "release");
+
+        let resolved = resolve_args_strict(&defined, &strs(&["--force", "--no-color"])).unwrap();
+        assert_eq!(resolved["force"], "--force");
+        assert_eq!(resolved["color"], "never");
+
+        let err = resolve_args(&defined, &strs(&["a", "b"])).unwrap_err();
+        assert!(matches!(
+            err,
+            MuuError::TooManyArgs {
+                expected: 1,
+                got: 2
+            }
+        ));
+    }
+
+    #[test]
+    fn negative_numbers_are_values() {
+        let defined = idx(&[("offset", "")]);
+        let resolved = resolve_args(&defined, &strs(&["-5"])).unwrap();
+        assert_eq!(resolved["offset"], "-5");
+    }
+
+    #[test]
+    fn ambiguous_args_error() {
+        let defined = idx(&[("dir", "."), ("bucket", "")]);
+        let err = resolve_args(&defined, &strs(&["--bucket"])).unwrap_err();
+        assert_eq!(err.to_string(), "argument 'bucket' needs a value");
+        let err = resolve_args(&defined, &strs(&["--bucket", "--dir=x"])).unwrap_err();
+        assert!(matches!(err, MuuError::MissingArgValue { .. }));
+        let err = resolve_args(&defined, &strs(&["--bucket=a", "bucket=b"])).unwrap_err();
+        assert_eq!(err.to_string(), "argument 'bucket' given more than once");
+    }
+
+    #[test]
+    fn unknown_bare_name_is_unknown() {
+        let defined = idx(&[("dir", ".")]);
+        let err = resolve_args(&defined, &strs(&["--force"])).unwrap_err();
+        assert_eq!(err.to_string(), "unknown argument 'force'");
+        let err = resolve_args(&IndexMap::new(), &strs(&["--force", "x"])).unwrap_err();
+        assert!(matches!(err, MuuError::UnknownArg { name } if name == "force"));
+    }
+
     #[test]
     fn no_args_with_defaults() {
         let defined = idx(&[("dir", "."), ("count", "10")]);
@@ -277,6 +749,247 @@ mod tests {
         assert_eq!(expand_command(cmd, &resolved), "long short");
     }
 
+    #[test]
+    fn quote_shell_words() {
+        assert_eq!(shell_quote("build:prod"), "build:prod");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    fn typed(kind: ArgType) -> ArgDef {
+        ArgDef {
+            kind,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn int_args_are_checked() {
+        let mut defined = IndexMap::new();
+        defined.insert(
+            "count".to_string(),
+            ArgDef {
+                default: "3".to_string(),
+                min: Some(1.0),
+                max: Some(10.0),
+                ..typed(ArgType::Int)
+            },
+        );
+        assert_eq!(resolve_args(&defined, &[]).unwrap()["count"], "3");
+        assert_eq!(
+            resolve_args(&defined, &strs(&["10"])).unwrap()["count"],
+            "10"
+        );
+
+        let err = resolve_args(&defined, &strs(&["three"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value 'three' for argument 'count': expected an integer"
+        );
+        let err = resolve_args(&defined, &strs(&["--count=11"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "argument 'count' must be between 1 and 10, got 11"
+        );
+    }
+
+    #[test]
+    fn float_bounds() {
+        let arg = ArgDef {
+            min: Some(0.5),
+            ..typed(ArgType::Float)
+        };
+        assert_eq!(check_value("ratio", &arg, "0.75").unwrap(), "0.75");
+        assert!(matches!(
+            check_value("ratio", &arg, "0.1").unwrap_err(),
+            MuuError::ArgOutOfRange { bound, .. } if bound == "at least 0.5"
+        ));
+        assert!(matches!(
+            check_value("ratio", &arg, "NaN").unwrap_err(),
+            MuuError::InvalidArgValue { .. }
+        ));
+    }
+
+    #[test]
+    fn bool_values_are_normalized() {
+        let arg = typed(ArgType::Bool);
+        assert_eq!(check_value("dry", &arg, "yes").unwrap(), "true");
+        assert_eq!(check_value("dry", &arg, "OFF").unwrap(), "false");
+        assert!(check_value("dry", &arg, "maybe").is_err());
+    }
+
+    #[test]
+    fn path_must_exist() {
+        let arg = ArgDef {
+            must_exist: true,
+            ..typed(ArgType::Path)
+        };
+        assert!(check_value("src", &arg, "Cargo.toml").is_ok());
+        let err = check_value("src", &arg, "no/such/file").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "path 'no/such/file' for argument 'src' does not exist"
+        );
+        assert!(check_value("src", &typed(ArgType::Path), "no/such/file").is_ok());
+    }
+
+    #[test]
+    fn enum_lists_choices() {
+        let arg = ArgDef {
+            choices: strs(&["dev", "prod"]),
+            ..typed(ArgType::Enum)
+        };
+        assert_eq!(check_value("env", &arg, "prod").unwrap(), "prod");
+        let err = check_value("env", &arg, "staging").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value 'staging' for argument 'env', expected one of: dev, prod"
+        );
+    }
+
+    #[test]
+    fn choices_apply_to_any_type() {
+        let mut defined = IndexMap::new();
+        defined.insert(
+            "env".to_string(),
+            ArgDef {
+                default: "dev".to_string(),
+                choices: strs(&["dev", "staging", "prod"]),
+                ..Default::default()
+            },
+        );
+        defined.insert(
+            "replicas".to_string(),
+            ArgDef {
+                default: "1".to_string(),
+                choices: strs(&["1", "3"]),
+                ..typed(ArgType::Int)
+            },
+        );
+        let resolved = resolve_args(&defined, &strs(&["--env=prod"])).unwrap();
+        assert_eq!(resolved["env"], "prod");
+        let err = resolve_args(&defined, &strs(&["qa"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value 'qa' for argument 'env', expected one of: dev, staging, prod"
+        );
+        let err = resolve_args(&defined, &strs(&["dev", "2"])).unwrap_err();
+        assert!(matches!(err, MuuError::InvalidChoice { name, .. } if name == "replicas"));
+    }
+
+    #[test]
+    fn empty_optional_values_skip_checks() {
+        let mut defined = IndexMap::new();
+        defined.insert(
+            "count".to_string(),
+            ArgDef {
+                optional: true,
+                ..typed(ArgType::Int)
+            },
+        );
+        assert_eq!(resolve_args(&defined, &[]).unwrap()["count"], "");
+    }
+
+    fn variadic(min_count: usize) -> ArgDef {
+        ArgDef {
+            optional: min_count == 0,
+            variadic: true,
+            min_count,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn variadic_collects_rest() {
+        let mut defined = idx(&[("dest", "")]);
+        defined.insert("files".to_string(), variadic(0));
+
+        let resolved = resolve_args(&defined, &strs(&["out", "a.txt", "my file.txt"])).unwrap();
+        assert_eq!(resolved["dest"], "out");
+        assert_eq!(resolved["files"], "a.txt 'my file.txt'");
+
+        let resolved = resolve_args(&defined, &strs(&["out"])).unwrap();
+        assert_eq!(resolved["files"], "");
+
+        let resolved =
+            resolve_args(&defined, &strs(&["--files=a", "--dest=out", "--files=b"])).unwrap();
+        assert_eq!(resolved["files"], "a b");
+    }
+
+    #[test]
+    fn variadic_min_count() {
+        let mut defined = IndexMap::new();
+        defined.insert("files".to_string(), variadic(2));
+        let err = resolve_args(&defined, &strs(&["a"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "argument 'files' needs at least 2 value(s), got 1"
+        );
+        assert!(resolve_args(&defined, &strs(&["a", "b"])).is_ok());
+    }
+
+    #[test]
+    fn variadic_values_are_checked() {
+        let mut defined = IndexMap::new();
+        defined.insert(
+            "ports".to_string(),
+            ArgDef {
+                kind: ArgType::Int,
+                ..variadic(1)
+            },
+        );
+        let err = resolve_args(&defined, &strs(&["80", "http"])).unwrap_err();
+        assert!(matches!(err, MuuError::InvalidArgValue { value, .. } if value == "http"));
+    }
+
+    #[test]
+    fn extra_positional_args_error() {
+        let defined = idx(&[("dir", ".")]);
+        let err = resolve_args(&defined, &strs(&["a", "b"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "too many arguments: expected at most 1, got 2"
+        );
+    }
+
+    #[test]
+    fn passthrough_after_double_dash() {
+        let defined = idx(&[("dir", ".")]);
+        let resolved =
+            resolve_args(&defined, &strs(&["src", "--", "--nocapture", "-q", "a b"])).unwrap();
+        assert_eq!(resolved["dir"], "src");
+        assert_eq!(resolved[PASSTHROUGH], "--nocapture -q 'a b'");
+        assert_eq!(
+            expand_command("test $dir $@ # $MUU_ARGS", &resolved),
+            "test src --nocapture -q 'a b' # --nocapture -q 'a b'"
+        );
+
+        let resolved = resolve_args(&defined, &strs(&["--dir=x", "--", "--dir=y"])).unwrap();
+        assert_eq!(resolved["dir"], "x");
+        assert_eq!(resolved[PASSTHROUGH], "--dir=y");
+
+        let resolved = resolve_args(&defined, &strs(&["--"])).unwrap();
+        assert_eq!(resolved[PASSTHROUGH], "");
+        assert!(
+            !resolve_args(&defined, &[])
+                .unwrap()
+                .contains_key(PASSTHROUGH)
+        );
+    }
+
+    #[test]
+    fn split_words_inverts_quoting() {
+        let values = ["plain", "two words", "it's", "", "$HOME"];
+        let joined = values.map(shell_quote).join(" ");
+        assert_eq!(split_words(&joined).unwrap(), values);
+        assert_eq!(
+            split_words(r#"a "b c" d\ e"#).unwrap(),
+            vec!["a", "b c", "d e"]
+        );
+        assert!(split_words("'open").is_none());
+    }
+
     #[test]
     fn no_defined_args_no_raw() {
         let defined: IndexMap<String, ArgDef> = IndexMap::new();
@@ -16,7 +16,9 @@ fn config_not_found() {
         .current_dir(dir.path())
         .assert()
         .failure()
-        .stderr(predicate::str::contains("no muu.toml or global config found"));
+        .stderr(predicate::str::contains(
+            "no muu.toml or global config found",
+        ));
 }
 
 #[test]
@@ -62,6 +64,8 @@ fn mixed_args_error() {
     std::fs::write(
         dir.path().join("muu.toml"),
         r#"
+strict_args = true
+
 [tasks.deploy]
 cmd = "echo $dir $bucket"
 args = { dir = ".", bucket = "" }
@@ -74,5 +78,39 @@ args = { dir = ".", bucket = "" }
         .current_dir(dir.path())
         .assert()
         .failure()
-        .stderr(predicate::str::contains("cannot mix positional and named arguments"));
+        .stderr(predicate::str::contains(
+            "cannot mix positional and named arguments",
+        ));
+}
+
+#[test]
+fn mixed_args_without_strict_args() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "echo $dir $bucket $tag"
+args.dir = "."
+args.bucket = { short = "b" }
+args.tag = "latest"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["deploy", "-b", "my-bucket", "./dist", "tag=v2"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("./dist my-bucket v2"));
+
+    muu()
+        .args(["deploy", "--bucket", "a", "--bucket=b"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "argument 'bucket' given more than once",
+        ));
 }
@@ -0,0 +1,216 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+#[allow(deprecated)]
+fn muu() -> Command {
+    Command::cargo_bin("muu").unwrap()
+}
+
+const CONFIG: &str = r#"
+[tasks.build]
+cmd = "cargo build"
+description = "Build the project"
+aliases = ["b"]
+
+[tasks.deploy]
+cmd = "aws s3 sync $dir s3://$bucket"
+args = { dir = "./dist", bucket = "" }
+
+[tasks."db:migrate"]
+cmd = "sqlx migrate run"
+"#;
+
+fn project() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("muu.toml"), CONFIG).unwrap();
+    dir
+}
+
+#[test]
+fn docs_markdown_to_stdout() {
+    let dir = project();
+
+    muu()
+        .args(["-l", "docs"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "### `build`\n\nBuild the project\n",
+        ))
+        .stdout(predicate::str::contains("- **Aliases:** b"))
+        .stdout(predicate::str::contains("muu deploy [dir] <bucket>"))
+        .stdout(predicate::str::contains("| `dir` | default: ./dist |"))
+        .stdout(predicate::str::contains("### `db`\n\n#### `db:migrate`"));
+}
+
+#[test]
+fn docs_splices_between_markers() {
+    let dir = project();
+    let readme = dir.path().join("README.md");
+    std::fs::write(
+        &readme,
+        "# App\n\n<!-- muu:docs -->\nstale\n<!-- /muu:docs -->\n\n## License\n",
+    )
+    .unwrap();
+
+    muu()
+        .args(["-l", "docs", "-o", "README.md"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote README.md"));
+
+    let content = std::fs::read_to_string(&readme).unwrap();
+    assert!(content.starts_with("# App\n\n<!-- muu:docs -->\n<!-- Generated by `muu docs`"));
+    assert!(content.ends_with("<!-- /muu:docs -->\n\n## License\n"));
+    assert!(!content.contains("stale"));
+    assert!(content.contains("### `deploy`"));
+}
+
+#[test]
+fn docs_keeps_files_it_did_not_generate() {
+    let dir = project();
+    let notes = dir.path().join("NOTES.md");
+    std::fs::write(&notes, "# Notes\n\nHand-written.\n").unwrap();
+
+    muu()
+        .args(["-l", "docs", "-o", "NOTES.md"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "NOTES.md exists and wasn't generated by muu",
+        ));
+    assert_eq!(
+        std::fs::read_to_string(&notes).unwrap(),
+        "# Notes\n\nHand-written.\n"
+    );
+
+    // A file muu wrote before is replaced as a whole
+    for _ in 0..2 {
+        muu()
+            .args(["-l", "docs", "-o", "TASKS.md"])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn docs_show_script_paths_relative_to_project() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("muu.toml"), "scripts_dir = \"scripts\"\n").unwrap();
+    let scripts = dir.path().join("scripts");
+    std::fs::create_dir(&scripts).unwrap();
+    let script = scripts.join("deploy.sh");
+    std::fs::write(&script, "#!/bin/sh\necho deploy\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    muu()
+        .args(["-l", "docs"])
+        .current_dir(&scripts)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("scripts/deploy.sh"))
+        .stdout(predicate::str::contains(dir.path().to_str().unwrap()).not());
+}
+
+#[test]
+fn docs_check_detects_stale_file() {
+    let dir = project();
+
+    muu()
+        .args(["-l", "docs", "-o", "TASKS.md", "--check"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("TASKS.md is out of date"));
+
+    muu()
+        .args(["-l", "docs", "-o", "TASKS.md"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    muu()
+        .args(["-l", "docs", "-o", "TASKS.md", "--check"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("TASKS.md is up to date"));
+
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        format!("{CONFIG}\n[tasks.lint]\ncmd = \"cargo clippy\"\n"),
+    )
+    .unwrap();
+
+    muu()
+        .args(["-l", "docs", "-o", "TASKS.md", "--check"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn docs_man_and_html() {
+    let dir = project();
+
+    muu()
+        .args(["-l", "docs", "--format", "man"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(".\\\" Generated by `muu docs`"))
+        .stdout(predicate::str::contains(".SS db:migrate"))
+        .stdout(predicate::str::contains("cargo build"));
+
+    muu()
+        .args(["-l", "docs", "--format", "html"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "<h3 id=\"task-build\"><code>build</code></h3>",
+        ))
+        .stdout(predicate::str::contains("muu deploy [dir] &lt;bucket&gt;"));
+}
+
+#[test]
+fn show_prints_task_help() {
+    let dir = project();
+    let expected = "Usage: muu deploy [dir] <bucket>\n\
+                    \n\
+                    Arguments:\n  \
+                    dir     default: ./dist\n  \
+                    bucket  required\n\
+                    \n\
+                    Runs:\n  \
+                    aws s3 sync $dir s3://$bucket\n";
+
+    muu()
+        .args(["show", "deploy"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(expected);
+
+    muu()
+        .args(["deploy", "--help"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(expected);
+
+    muu()
+        .args(["show", "nope"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("nope"));
+}
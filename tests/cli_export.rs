@@ -0,0 +1,476 @@
+use std::path::Path;
+use std::process::Output;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+#[allow(deprecated)]
+fn muu() -> Command {
+    Command::cargo_bin("muu").unwrap()
+}
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/export");
+
+/// Export the fixture config and compare with the golden file. Run with
+/// `UPDATE_GOLDEN=1` to regenerate the golden files.
+fn check_golden(args: &[&str], golden: &str) {
+    let dir = TempDir::new().unwrap();
+    std::fs::copy(
+        Path::new(FIXTURES).join("muu.toml"),
+        dir.path().join("muu.toml"),
+    )
+    .unwrap();
+
+    let output = muu()
+        .args(args)
+        .arg("-l")
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let golden = Path::new(FIXTURES).join(golden);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&golden, &actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&golden).unwrap();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn export_makefile() {
+    check_golden(&["export", "makefile"], "Makefile");
+}
+
+#[test]
+fn export_justfile() {
+    check_golden(&["export", "justfile"], "justfile");
+}
+
+#[test]
+fn export_vscode() {
+    check_golden(&["export", "vscode"], "tasks.json");
+}
+
+#[test]
+fn export_github_actions() {
+    check_golden(&["export", "github-actions"], "workflow.yml");
+}
+
+#[test]
+fn export_to_file() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        "[tasks.hello]\ncmd = \"echo hello\"\n",
+    )
+    .unwrap();
+
+    muu()
+        .args(["export", "vscode", "-l", "-o", ".vscode/tasks.json"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Exported 1 task(s) to .vscode/tasks.json",
+        ));
+
+    let content = std::fs::read_to_string(dir.path().join(".vscode/tasks.json")).unwrap();
+    assert!(content.contains("\"label\": \"hello\""));
+}
+
+#[test]
+fn export_notes_computed_defaults() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "deploy $bucket"
+args = { env = "dev", bucket = "{{ env }}-bucket" }
+"#,
+    )
+    .unwrap();
+
+    for args in [
+        &["export", "makefile"][..],
+        &["export", "justfile"],
+        &["export", "vscode"],
+        &["export", "github-actions"],
+        &["export-script", "deploy"],
+    ] {
+        muu()
+            .args(args)
+            .arg("-l")
+            .current_dir(dir.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "TODO: `bucket` defaults to {{ env }}-bucket in muu.toml",
+            ));
+    }
+}
+
+#[test]
+fn export_script_golden() {
+    check_golden(&["export-script", "deploy"], "deploy.sh");
+}
+
+/// Write a script for `task` into `dir` and run it with `sh` and `args`.
+fn run_script(dir: &TempDir, task: &str, args: &[&str]) -> Output {
+    let path = dir.path().join(format!("{task}.sh"));
+    muu()
+        .args(["export-script", task, "-l", "-o"])
+        .arg(&path)
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    std::process::Command::new("sh")
+        .arg(&path)
+        .args(args)
+        .current_dir(dir.path())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn export_script_resolves_args_like_muu() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "echo \"deploy $dir to $bucket as [$name]\""
+args = { dir = ".", bucket = { short = "b" }, name = "?" }
+preconditions = [{ sh = "test $bucket != forbidden", msg = "bucket is forbidden" }]
+"#,
+    )
+    .unwrap();
+
+    let stdout = |out: &Output| String::from_utf8_lossy(&out.stdout).into_owned();
+    let stderr = |out: &Output| String::from_utf8_lossy(&out.stderr).into_owned();
+
+    let out = run_script(&dir, "deploy", &["./dist", "my-bucket"]);
+    assert!(out.status.success());
+    assert_eq!(stdout(&out), "deploy ./dist to my-bucket as []\n");
+
+    let out = run_script(&dir, "deploy", &["--bucket=b", "--name=it's"]);
+    assert_eq!(stdout(&out), "deploy . to b as [it's]\n");
+
+    let out = run_script(&dir, "deploy", &["./dist"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(stderr(&out).contains("missing required argument 'bucket'"));
+
+    let out = run_script(&dir, "deploy", &["./dist", "--bucket", "b"]);
+    assert_eq!(stdout(&out), "deploy ./dist to b as []\n");
+
+    let out = run_script(&dir, "deploy", &["-b", "b", "name=x", "./dist"]);
+    assert_eq!(stdout(&out), "deploy ./dist to b as [x]\n");
+
+    let out = run_script(&dir, "deploy", &["-b=b", "--dir=-1"]);
+    assert_eq!(stdout(&out), "deploy -1 to b as []\n");
+
+    let out = run_script(&dir, "deploy", &["--bucket=a", "bucket=b"]);
+    assert!(stderr(&out).contains("argument 'bucket' given more than once"));
+
+    let out = run_script(&dir, "deploy", &["--bucket", "--dir=x"]);
+    assert!(stderr(&out).contains("argument 'bucket' needs a value"));
+
+    let out = run_script(&dir, "deploy", &["--force"]);
+    assert!(stderr(&out).contains("unknown argument 'force'"));
+
+    let out = run_script(&dir, "deploy", &["-x"]);
+    assert!(stderr(&out).contains("unknown argument '-x'"));
+
+    let out = run_script(&dir, "deploy", &["--typo=x"]);
+    assert!(stderr(&out).contains("unknown argument 'typo'"));
+
+    let out = run_script(&dir, "deploy", &["./dist", "b", "n", "extra"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(stderr(&out).contains("too many arguments: expected at most 3, got 4"));
+
+    let out = run_script(&dir, "deploy", &["--bucket=forbidden"]);
+    assert_eq!(out.status.code(), Some(3));
+    assert!(stderr(&out).contains("bucket is forbidden"));
+}
+
+#[test]
+fn export_script_checks_values() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "echo deploy $env $count $verbose $src"
+args.env = { choices = ["dev", "prod"] }
+args.count = { type = "int", max = 3, default = 1 }
+args.verbose = { type = "bool", default = "no" }
+args.src = { type = "path", must_exist = true, optional = true }
+"#,
+    )
+    .unwrap();
+    let stdout = |out: &Output| String::from_utf8_lossy(&out.stdout).into_owned();
+    let stderr = |out: &Output| String::from_utf8_lossy(&out.stderr).into_owned();
+
+    let out = run_script(&dir, "deploy", &["dev", "3", "yes", "muu.toml"]);
+    assert_eq!(stdout(&out), "deploy dev 3 true muu.toml\n");
+
+    let out = run_script(&dir, "deploy", &["qa"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(
+        stderr(&out).contains("invalid value 'qa' for argument 'env', expected one of: dev, prod")
+    );
+
+    let out = run_script(&dir, "deploy", &["dev", "9"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(stderr(&out).contains("argument 'count' must be at most 3, got 9"));
+
+    let out = run_script(&dir, "deploy", &["dev", "three"]);
+    assert!(
+        stderr(&out).contains("invalid value 'three' for argument 'count': expected an integer")
+    );
+
+    let out = run_script(&dir, "deploy", &["dev", "--verbose=maybe"]);
+    assert!(
+        stderr(&out)
+            .contains("invalid value 'maybe' for argument 'verbose': expected true or false")
+    );
+
+    let out = run_script(&dir, "deploy", &["dev", "--src=missing"]);
+    assert!(stderr(&out).contains("path 'missing' for argument 'src' does not exist"));
+}
+
+#[test]
+fn export_script_falls_back_to_env() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "echo deploy $bucket $region; printf '[%s]' $files; echo"
+args.bucket = { env = "DEPLOY_BUCKET" }
+args.region = { env = "DEPLOY_REGION", default = "us" }
+args.files = { variadic = true, env = "DEPLOY_FILES" }
+"#,
+    )
+    .unwrap();
+    let path = dir.path().join("deploy.sh");
+    muu()
+        .args(["export-script", "deploy", "-l", "-o"])
+        .arg(&path)
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    let run = |env: &[(&str, &str)], args: &[&str]| {
+        std::process::Command::new("sh")
+            .arg(&path)
+            .args(args)
+            .envs(env.iter().copied())
+            .current_dir(dir.path())
+            .output()
+            .unwrap()
+    };
+    let stdout = |out: &Output| String::from_utf8_lossy(&out.stdout).into_owned();
+
+    let out = run(
+        &[("DEPLOY_BUCKET", "envb"), ("DEPLOY_FILES", "a 'b c'")],
+        &[],
+    );
+    assert_eq!(stdout(&out), "deploy envb us\n[a][b c]\n");
+
+    let out = run(
+        &[("DEPLOY_BUCKET", "envb"), ("DEPLOY_REGION", "eu")],
+        &["given"],
+    );
+    assert_eq!(stdout(&out), "deploy given eu\n[]\n");
+
+    let out = run(&[("DEPLOY_BUCKET", "")], &[]);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("missing required argument 'bucket'"));
+}
+
+#[test]
+fn export_script_strict_args() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "echo \"deploy $dir to $bucket\""
+args = { dir = ".", bucket = "" }
+strict_args = true
+"#,
+    )
+    .unwrap();
+    let stdout = |out: &Output| String::from_utf8_lossy(&out.stdout).into_owned();
+
+    let out = run_script(&dir, "deploy", &["--bucket=a", "--bucket=b"]);
+    assert_eq!(stdout(&out), "deploy . to b\n");
+
+    let out = run_script(&dir, "deploy", &["bucket=x", "b"]);
+    assert_eq!(stdout(&out), "deploy bucket=x to b\n");
+
+    let out = run_script(&dir, "deploy", &["./dist", "--bucket=b"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("cannot mix positional and named arguments")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn export_script_collects_variadic_args() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"scripts_dir = "scripts"
+
+[tasks.lint]
+cmd = "printf '%s:' $level; printf '[%s]' $files; echo"
+args.level = "warn"
+args.files = { variadic = true, min_count = 1 }
+"#,
+    )
+    .unwrap();
+    std::fs::create_dir(dir.path().join("scripts")).unwrap();
+    let path = dir.path().join("scripts/list.sh");
+    std::fs::write(
+        &path,
+        "#!/bin/sh\n# muu:arg files=...\nprintf '[%s]' \"$@\"\necho\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let stdout = |out: &Output| String::from_utf8_lossy(&out.stdout).into_owned();
+
+    let out = run_script(&dir, "lint", &["error", "a.js", "my file.js", "it's"]);
+    assert!(out.status.success());
+    assert_eq!(stdout(&out), "error:[a.js][my file.js][it's]\n");
+
+    let out = run_script(
+        &dir,
+        "lint",
+        &["--files=a.js", "--files=b c", "--level=info"],
+    );
+    assert_eq!(stdout(&out), "info:[a.js][b c]\n");
+
+    let out = run_script(&dir, "lint", &["error"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(
+        String::from_utf8_lossy(&out.stderr)
+            .contains("argument 'files' needs at least 1 value(s), got 0")
+    );
+
+    let out = run_script(&dir, "list", &["a", "b c"]);
+    assert_eq!(stdout(&out), "[a][b c]\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn export_script_passes_words_after_dashes() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"scripts_dir = "scripts"
+
+[tasks.test]
+cmd = "printf '<%s>' $target $@; echo"
+args.target = "all"
+
+[tasks.fmt]
+cmd = "printf '<%s>'"
+passthrough = true
+
+[tasks.build]
+cmd = "echo build"
+"#,
+    )
+    .unwrap();
+    std::fs::create_dir(dir.path().join("scripts")).unwrap();
+    let path = dir.path().join("scripts/list.sh");
+    std::fs::write(
+        &path,
+        "#!/bin/sh
+printf '[%s]' \"$@\"\necho\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let stdout = |out: &Output| String::from_utf8_lossy(&out.stdout).into_owned();
+
+    let out = run_script(&dir, "test", &["unit", "--", "-v", "it's a test"]);
+    assert!(out.status.success());
+    assert_eq!(stdout(&out), "<unit><-v><it's a test>\n");
+
+    let out = run_script(&dir, "test", &["--", "--target=x"]);
+    assert_eq!(stdout(&out), "<all><--target=x>\n");
+
+    let out = run_script(&dir, "fmt", &["--", "a", "b c"]);
+    assert_eq!(stdout(&out), "<a><b c>");
+
+    let out = run_script(&dir, "list", &["--", "a", "b c"]);
+    assert_eq!(stdout(&out), "[a][b c]\n");
+
+    let out = run_script(&dir, "build", &["--", "a"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(
+        String::from_utf8_lossy(&out.stderr)
+            .contains("task 'build' does not take arguments after `--`")
+    );
+}
+
+#[test]
+fn export_script_maps_flags() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.build]
+cmd = "echo \"build $dir [$release]\""
+args.dir = "."
+args.release = { type = "flag", on = "--release", off = "--debug" }
+"#,
+    )
+    .unwrap();
+    let stdout = |out: &Output| String::from_utf8_lossy(&out.stdout).into_owned();
+
+    let out = run_script(&dir, "build", &["src"]);
+    assert_eq!(stdout(&out), "build src [--debug]\n");
+
+    let out = run_script(&dir, "build", &["--release"]);
+    assert_eq!(stdout(&out), "build . [--release]\n");
+
+    let out = run_script(&dir, "build", &["--release=yes", "--dir=src"]);
+    assert_eq!(stdout(&out), "build src [--release]\n");
+
+    let out = run_script(&dir, "build", &["--no-release", "src"]);
+    assert_eq!(stdout(&out), "build src [--debug]\n");
+
+    let out = run_script(&dir, "build", &["--release", "--no-release"]);
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("argument 'release' given more than once")
+    );
+
+    let out = run_script(&dir, "build", &["--release=maybe"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(
+        String::from_utf8_lossy(&out.stderr)
+            .contains("invalid value 'maybe' for argument 'release': expected true or false")
+    );
+}
+
+#[test]
+fn export_script_unknown_task() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("muu.toml"), "[tasks.a]\ncmd = \"true\"\n").unwrap();
+    muu()
+        .args(["export-script", "nope", "-l"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("task 'nope' not found"));
+}
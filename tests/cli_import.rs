@@ -0,0 +1,121 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+#[allow(deprecated)]
+fn muu() -> Command {
+    Command::cargo_bin("muu").unwrap()
+}
+
+const MAKEFILE: &str = "ENV ?= dev\n\
+.PHONY: build deploy\n\
+\n\
+build: ## Build the site\n\
+\tnpm run build\n\
+\n\
+deploy: build ## Deploy the site\n\
+\t./deploy.sh $(ENV) $<\n";
+
+#[test]
+fn import_dry_run_prints_toml() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("Makefile"), MAKEFILE).unwrap();
+
+    muu()
+        .args(["import", "make", "--dry-run"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "[tasks.build]\ncmd = \"npm run build\"\ndescription = \"Build the site\"\n",
+        ))
+        .stdout(predicate::str::contains(
+            "# TODO: automatic variable `$<` kept as is",
+        ))
+        .stdout(predicate::str::contains("args = { env = \"dev\" }"))
+        .stderr(predicate::str::contains(
+            "warning: deploy: automatic variable `$<` kept as is",
+        ));
+
+    assert!(!dir.path().join("muu.toml").exists());
+}
+
+#[test]
+fn import_writes_runnable_tasks() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("justfile"),
+        "greet name=\"world\":\n    echo hello {{name}}\n",
+    )
+    .unwrap();
+
+    muu()
+        .args(["import", "just"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Imported 1 task(s) from justfile into muu.toml",
+        ));
+
+    muu()
+        .args(["greet", "muu"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello muu"));
+}
+
+#[test]
+fn import_appends_and_skips_existing() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        "[tasks.build]\ncmd = \"make build\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("Makefile"), MAKEFILE).unwrap();
+
+    muu()
+        .args(["import", "make"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "skipping 'build', already defined in muu.toml",
+        ));
+
+    let content = std::fs::read_to_string(dir.path().join("muu.toml")).unwrap();
+    assert!(content.starts_with("[tasks.build]\ncmd = \"make build\"\n\n"));
+    assert!(content.contains("[tasks.deploy]"));
+    assert_eq!(content.matches("[tasks.build]").count(), 1);
+}
+
+#[test]
+fn import_explicit_path() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir(dir.path().join("web")).unwrap();
+    std::fs::write(
+        dir.path().join("web/package.json"),
+        r#"{ "scripts": { "dev": "vite" } }"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["import", "npm", "web/package.json", "--dry-run"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("[tasks.dev]\ncmd = \"vite\"\n");
+}
+
+#[test]
+fn import_missing_source() {
+    let dir = TempDir::new().unwrap();
+    muu()
+        .args(["import", "taskfile"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Taskfile.yml not found"));
+}
@@ -45,7 +45,9 @@ fn list_no_config() {
         .current_dir(dir.path())
         .assert()
         .failure()
-        .stderr(predicate::str::contains("no muu.toml or global config found"));
+        .stderr(predicate::str::contains(
+            "no muu.toml or global config found",
+        ));
 }
 
 #[test]
@@ -70,3 +72,161 @@ description = "Local task"
         .stdout(predicate::str::contains("local_task"))
         .stdout(predicate::str::contains("[local]"));
 }
+
+#[test]
+fn list_shows_aliases() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.build]
+cmd = "cargo build"
+description = "Build the project"
+aliases = ["b", "bld"]
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["-l", "list"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("build (b, bld)"));
+}
+
+#[test]
+fn list_groups_namespaces() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks."db:migrate"]
+cmd = "migrate"
+
+[tasks.build]
+cmd = "cargo build"
+
+[tasks.db.seed]
+cmd = "seed"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["-l", "list"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"db:\x1b\[22m\n  migrate +.*\n  seed ").unwrap())
+        .stdout(predicate::str::contains("build"));
+}
+
+#[test]
+fn list_filters_by_tag() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.lint]
+cmd = "eslint"
+tags = ["ci", "frontend"]
+
+[tasks.migrate]
+cmd = "migrate"
+tags = ["ci", "backend"]
+
+[tasks.dev]
+cmd = "vite"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["-l", "list", "-t", "ci,frontend"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("lint"))
+        .stdout(predicate::str::contains("#ci #frontend"))
+        .stdout(predicate::str::contains("migrate").not())
+        .stdout(predicate::str::contains("dev").not());
+
+    muu()
+        .args(["-l", "list", "-t", "frontend", "-t", "backend"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("lint"))
+        .stdout(predicate::str::contains("migrate"));
+
+    muu()
+        .args(["-l", "list", "-t", "nope"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no tasks match tag filter 'nope'"));
+}
+
+#[test]
+fn list_marks_deprecated() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.old]
+cmd = "echo old"
+replaced_by = "new"
+
+[tasks.new]
+cmd = "echo new"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["-l", "list"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"old .*\(deprecated\)").unwrap())
+        .stdout(
+            predicate::str::is_match(r"new .*\(deprecated\)")
+                .unwrap()
+                .not(),
+        );
+}
+
+#[test]
+fn list_includes_provider_tasks() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+providers = ["npm", "make"]
+
+[tasks.build]
+cmd = "echo muu build"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("package.json"),
+        r#"{ "scripts": { "build": "vite build", "dev": "vite" } }"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("Makefile"),
+        "clean: ## Remove build output\n\trm -rf dist\n",
+    )
+    .unwrap();
+
+    muu()
+        .args(["-l", "list"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"build .*\[local\]").unwrap())
+        .stdout(predicate::str::is_match(r"dev .*vite .*\[npm\]").unwrap())
+        .stdout(predicate::str::is_match(r"clean .*Remove build output .*\[make\]").unwrap());
+}
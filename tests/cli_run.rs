@@ -27,6 +27,56 @@ cmd = "echo hello"
         .stdout(predicate::str::contains("hello"));
 }
 
+#[test]
+fn tasks_win_over_subcommands_of_the_same_name() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.run]
+cmd = "echo running $what"
+args = { what = "all" }
+
+[tasks.build]
+cmd = "echo build"
+aliases = ["docs"]
+
+[tasks.show]
+cmd = "echo show"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["-l", "run", "tests"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("running tests"));
+    muu()
+        .arg("docs")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("build"));
+    muu()
+        .arg("show")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("show"));
+
+    // Without such a task the subcommand works as usual
+    muu()
+        .args(["-l", "export", "makefile"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Generated by `muu export makefile`",
+        ));
+}
+
 #[test]
 fn run_with_positional_args() {
     let dir = TempDir::new().unwrap();
@@ -168,3 +218,958 @@ fn version_flag() {
         .success()
         .stdout(predicate::str::contains("muu"));
 }
+
+#[test]
+fn run_by_alias() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.build]
+cmd = "echo building $target"
+args = { target = "debug" }
+aliases = ["b", "bld"]
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["bld", "release"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("building release"));
+}
+
+#[test]
+fn alias_clash_is_error() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.build]
+cmd = "echo build"
+aliases = ["test"]
+
+[tasks.test]
+cmd = "echo test"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .arg("build")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("duplicate task 'test'"));
+}
+
+#[test]
+fn run_nested_namespace_task() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.db.migrate]
+cmd = "echo migrating"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .arg("db:migrate")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("migrating"));
+}
+
+#[test]
+fn run_namespace_opens_selector() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.db.migrate]
+cmd = "echo migrating"
+"#,
+    )
+    .unwrap();
+
+    // The selector cannot prompt without a TTY, but it must not be a lookup error
+    muu()
+        .arg("db")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not found").not());
+}
+
+#[test]
+fn run_batch_by_tag() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.lint]
+cmd = "echo linting"
+tags = ["ci"]
+
+[tasks.dev]
+cmd = "echo dev-server"
+
+[tasks.test]
+cmd = "echo testing"
+tags = ["ci", "backend"]
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["run", "--tag", "ci"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("(?s)linting.*testing").unwrap())
+        .stdout(predicate::str::contains("dev-server").not());
+}
+
+#[test]
+fn run_batch_stops_on_failure() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.first]
+cmd = "exit 3"
+tags = ["ci"]
+
+[tasks.second]
+cmd = "echo second"
+tags = ["ci"]
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["run", "-t", "ci"])
+        .current_dir(dir.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("second").not());
+}
+
+#[test]
+fn run_batch_redirects_deprecated_tasks() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.lint]
+cmd = "echo old-lint"
+tags = ["ci"]
+deprecated = "use `check` instead"
+replaced_by = "check"
+
+[tasks.check]
+cmd = "echo new-check"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["run", "-t", "ci"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("new-check"))
+        .stdout(predicate::str::contains("old-lint").not())
+        .stderr(predicate::str::contains(
+            "task 'lint' is deprecated: use `check` instead",
+        ));
+}
+
+#[test]
+fn run_batch_requires_tag() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("muu.toml"), "[tasks]\n").unwrap();
+
+    muu()
+        .arg("run")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("requires at least one --tag"));
+}
+
+#[test]
+fn run_deprecated_redirects() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.migrate]
+cmd = "echo old $steps"
+args = { steps = "1" }
+deprecated = "use `db:migrate` instead"
+replaced_by = "db:migrate"
+
+[tasks."db:migrate"]
+cmd = "echo new $steps"
+args = { steps = "1" }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["migrate", "5"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("new 5"))
+        .stderr(predicate::str::contains(
+            "task 'migrate' is deprecated: use `db:migrate` instead",
+        ));
+
+    muu()
+        .args(["--strict", "migrate", "5"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("old 5"));
+}
+
+#[test]
+fn run_deprecated_without_replacement() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.legacy]
+cmd = "echo still works"
+deprecated = "will be removed in v2"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .arg("legacy")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("still works"))
+        .stderr(predicate::str::contains("will be removed in v2"));
+}
+
+#[test]
+fn run_unmet_requirements_reported_together() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+requires = ["muu-missing-tool-a"]
+
+[tasks.build]
+cmd = "echo should-not-run"
+requires = [
+    "muu-missing-tool-b",
+    { tool = "sh", version = ">= 99", probe = "echo 2.3.4" },
+    { tool = "sh", version = "^2.3", probe = "echo 2.3.4" },
+]
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .arg("build")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("should-not-run").not())
+        .stderr(predicate::str::contains("muu-missing-tool-a"))
+        .stderr(predicate::str::contains("muu-missing-tool-b"))
+        .stderr(predicate::str::contains("sh >= 99: found 2.3.4"))
+        .stderr(predicate::str::contains("^2.3").not());
+}
+
+#[test]
+fn run_precondition_failure() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.start]
+cmd = "echo starting"
+preconditions = [
+    { sh = "true", msg = "never shown" },
+    { sh = "test -f .env", msg = "run `muu setup` first" },
+]
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .arg("start")
+        .current_dir(dir.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("starting").not())
+        .stderr(predicate::str::contains("run `muu setup` first"))
+        .stderr(predicate::str::contains("never shown").not())
+        .stderr(predicate::str::contains("$ echo").not());
+
+    std::fs::write(dir.path().join(".env"), "").unwrap();
+    muu()
+        .arg("start")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("starting"));
+}
+
+#[test]
+fn run_precondition_uses_args() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.open]
+cmd = "cat $file"
+args = { file = "" }
+preconditions = [{ sh = "test -f $file" }]
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["open", "missing.txt"])
+        .current_dir(dir.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(
+            "precondition failed: test -f missing.txt",
+        ));
+}
+
+#[test]
+fn run_skips_when_status_satisfied() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.setup]
+cmd = "echo installing && touch .installed"
+status = ["test -f .installed", "true"]
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .arg("setup")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("installing"));
+
+    muu()
+        .arg("setup")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("installing").not())
+        .stderr(predicate::str::contains("task 'setup' is up to date"));
+
+    muu()
+        .args(["--force", "setup"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("installing"));
+}
+
+#[test]
+fn run_matrix_from_config() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "echo deploy-$env-$region"
+args = { env = "", region = "" }
+matrix = { env = ["dev", "stg"], region = ["us", "eu"] }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .arg("deploy")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                "(?s)deploy-dev-us.*deploy-dev-eu.*deploy-stg-us.*deploy-stg-eu",
+            )
+            .unwrap(),
+        )
+        .stderr(predicate::str::is_match(r"env +region +result").unwrap());
+}
+
+#[test]
+fn run_matrix_from_cli_parallel_with_failure() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.check]
+cmd = "echo checking $target; test $target != bad"
+args = { target = "" }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["--matrix", "target=good,bad", "--parallel", "check"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("[target=good] checking good"))
+        .stdout(predicate::str::contains("[target=bad] checking bad"))
+        .stderr(predicate::str::is_match(r"good +ok").unwrap())
+        .stderr(predicate::str::is_match(r"bad +failed \(exit 1\)").unwrap());
+}
+
+#[test]
+fn run_matrix_checks_and_expands_cell_values() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.build]
+cmd = "echo build $files $release"
+args.release = { type = "flag", on = "--release", off = "--debug" }
+args.jobs = { type = "int", default = 1 }
+args.files = { variadic = true }
+matrix = { release = ["yes", "no"], files = ["a b"] }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .arg("build")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("build a b --release"))
+        .stdout(predicate::str::contains("build a b --debug"));
+
+    muu()
+        .args(["--matrix", "jobs=2,many", "build"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "invalid value 'many' for argument 'jobs': expected an integer",
+        ));
+}
+
+#[test]
+fn run_matrix_keeps_precondition_exit_code() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "echo deploy $env"
+args = { env = "" }
+matrix = { env = ["dev", "prod"] }
+preconditions = [{ sh = "test $env != prod", msg = "not on prod" }]
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .arg("deploy")
+        .current_dir(dir.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("deploy dev"))
+        .stderr(predicate::str::contains("error: not on prod"));
+}
+
+#[test]
+fn run_matrix_leaves_positional_args_to_other_args() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "echo deploy $env $target"
+args = { env = "", target = "debug" }
+matrix = { env = ["dev", "prod"] }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["deploy", "release"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("deploy dev release"))
+        .stdout(predicate::str::contains("deploy prod release"));
+
+    muu()
+        .args(["deploy", "--env=qa"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("deploy").not())
+        .stderr(predicate::str::contains(
+            "argument 'env' is set by the matrix",
+        ));
+}
+
+#[test]
+fn run_matrix_unknown_arg() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.check]
+cmd = "echo $target"
+args = { target = "" }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["--matrix", "nope=a,b", "check"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown argument 'nope'"));
+}
+
+#[cfg(unix)]
+#[test]
+fn run_script_from_scripts_dir() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+scripts_dir = "scripts"
+
+[tasks.build]
+cmd = "echo explicit build"
+"#,
+    )
+    .unwrap();
+    let scripts = dir.path().join("scripts");
+    std::fs::create_dir(&scripts).unwrap();
+    for (name, body) in [
+        (
+            "greet.sh",
+            "#!/bin/sh\n# muu:description Greet someone\n# muu:arg name\n# muu:arg greeting=hello\necho \"$2, $1 from script\"\n",
+        ),
+        ("build.sh", "#!/bin/sh\necho script build\n"),
+    ] {
+        let path = scripts.join(name);
+        std::fs::write(&path, body).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    muu()
+        .args(["greet", "Alice"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello, Alice from script"));
+
+    muu()
+        .arg("greet")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("missing required argument 'name'"));
+
+    // Explicit tasks win over scripts with the same name
+    muu()
+        .arg("build")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("explicit build"));
+
+    muu()
+        .args(["-l", "list"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Greet someone"))
+        .stdout(predicate::str::contains("[script]"));
+}
+
+#[test]
+fn run_make_provider_task() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("muu.toml"), "providers = [\"make\"]\n").unwrap();
+    std::fs::write(
+        dir.path().join("Makefile"),
+        "hello: ## Say hello\n\t@echo hello from make $(name)\n",
+    )
+    .unwrap();
+
+    let child = dir.path().join("sub");
+    std::fs::create_dir(&child).unwrap();
+    muu()
+        .arg("hello")
+        .current_dir(&child)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello from make"));
+
+    // Args after `--` go to make
+    muu()
+        .args(["hello", "--", "name=muu"])
+        .current_dir(&child)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello from make muu"));
+}
+
+#[test]
+fn run_checks_typed_args() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.retry]
+cmd = "echo retry $count $verbose"
+args.count = { type = "int", default = 3, min = 1, max = 10 }
+args.verbose = { type = "bool", default = false }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["retry", "5", "yes"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("retry 5 true"));
+
+    muu()
+        .args(["retry", "--count=0"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "argument 'count' must be between 1 and 10, got 0",
+        ));
+
+    muu()
+        .args(["retry", "many"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected an integer"));
+}
+
+#[test]
+fn run_rejects_values_outside_choices() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "echo deploying to $env"
+args.env = { choices = ["dev", "staging", "prod"], default = "dev" }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["deploy", "staging"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("deploying to staging"));
+
+    muu()
+        .args(["deploy", "qa"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "invalid value 'qa' for argument 'env', expected one of: dev, staging, prod",
+        ));
+}
+
+#[test]
+fn run_checks_choices_from_command() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.checkout]
+cmd = "echo checking out $branch"
+args.branch = { choices_cmd = "printf 'main\\nfeature/x\\n'" }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["checkout", "feature/x"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("checking out feature/x"));
+
+    muu()
+        .args(["checkout", "release"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected one of: main, feature/x"));
+}
+
+#[test]
+fn run_with_variadic_args() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.count]
+cmd = "printf '<%s>' $files; echo"
+args = { label = "", files = "..." }
+
+[tasks.hello]
+cmd = "echo hello $name"
+args = { name = "world" }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["count", "x", "a.txt", "my file.txt"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<a.txt><my file.txt>"));
+
+    muu()
+        .args(["hello", "Alice", "Bob"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "too many arguments: expected at most 1, got 2",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn run_script_with_variadic_args() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("muu.toml"), "scripts_dir = \"scripts\"\n").unwrap();
+    let scripts = dir.path().join("scripts");
+    std::fs::create_dir(&scripts).unwrap();
+    let path = scripts.join("each.sh");
+    std::fs::write(
+        &path,
+        "#!/bin/sh\n# muu:arg files=...\necho \"$# args\"; printf '<%s>' \"$@\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    muu()
+        .args(["each", "a", "b c"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 args\n<a><b c>"));
+}
+
+#[test]
+fn run_passes_args_after_double_dash() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.test]
+cmd = "printf '<%s>' $name"
+args = { name = "all" }
+passthrough = true
+
+[tasks.wrap]
+cmd = "printf '<%s>' $MUU_ARGS end"
+
+[tasks.plain]
+cmd = "echo plain"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["test", "unit", "--", "--nocapture", "a b"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("<unit><--nocapture><a b>");
+
+    muu()
+        .args(["wrap", "--", "--name=x"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("<--name=x><end>");
+
+    muu()
+        .args(["plain", "--", "x"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "task 'plain' does not take arguments after `--`",
+        ));
+}
+
+#[test]
+fn run_with_flag_args() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.build]
+cmd = "echo build $target $release $verbose"
+args.target = "debug"
+args.release = { type = "flag", on = "--release", off = "" }
+args.verbose = { type = "flag", short = "v", default = true }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["build"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("build debug true\n");
+
+    muu()
+        .args(["build", "--release", "x86", "--no-verbose"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("build x86 --release false\n");
+
+    muu()
+        .args(["build", "-v", "--release=no"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("build debug true\n");
+}
+
+#[test]
+fn run_reads_args_from_env() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "echo $bucket $region"
+args.bucket = { env = "MUU_TEST_BUCKET" }
+args.region = { env = "MUU_TEST_REGION", default = "eu" }
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["deploy"])
+        .env("MUU_TEST_BUCKET", "from-env")
+        .env_remove("MUU_TEST_REGION")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("from-env eu\n");
+
+    muu()
+        .args(["deploy", "cli"])
+        .env("MUU_TEST_BUCKET", "from-env")
+        .env("MUU_TEST_REGION", "us")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("cli us\n");
+
+    muu()
+        .args(["deploy"])
+        .env_remove("MUU_TEST_BUCKET")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("bucket"));
+}
+
+#[test]
+fn run_with_computed_defaults() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+[tasks.deploy]
+cmd = "echo $bucket $label"
+args.bucket = "{{ env }}-bucket"
+args.env = "dev"
+args.label = { default = { sh = "echo {{ bucket }} | tr a-z A-Z" } }
+
+[tasks.loop]
+cmd = "echo $a $b"
+args.a = "{{ b }}"
+args.b = "{{ a }}"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .args(["deploy"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("dev-bucket DEV-BUCKET\n");
+
+    muu()
+        .args(["deploy", "--env", "prod"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("prod-bucket PROD-BUCKET\n");
+
+    muu()
+        .args(["deploy", "mine"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("mine MINE\n");
+
+    muu()
+        .args(["loop"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("a -> b -> a"));
+
+    muu()
+        .args(["loop", "--b", "x"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("x x\n");
+}
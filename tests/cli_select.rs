@@ -15,7 +15,9 @@ fn select_no_config() {
         .current_dir(dir.path())
         .assert()
         .failure()
-        .stderr(predicate::str::contains("no muu.toml or global config found"));
+        .stderr(predicate::str::contains(
+            "no muu.toml or global config found",
+        ));
 }
 
 #[test]
@@ -34,7 +36,7 @@ fn select_no_tasks_defined() {
 }
 
 #[test]
-fn select_non_tty_does_not_panic() {
+fn select_non_tty_lists_tasks() {
     let dir = TempDir::new().unwrap();
     std::fs::write(
         dir.path().join("muu.toml"),
@@ -45,10 +47,84 @@ cmd = "echo hello"
     )
     .unwrap();
 
-    // In a non-TTY environment (CI, piped stdin), inquire will fail gracefully
-    // We just verify it doesn't panic and exits with a non-zero code
+    // Without a TTY (CI, piped stdin) a bare `muu` prints the task list
+    // instead of prompting, and exits with a distinct code
     muu()
         .current_dir(dir.path())
         .assert()
-        .failure();
+        .code(4)
+        .stdout(predicate::str::contains("hello"));
+}
+
+#[test]
+fn select_subcommand_non_tty_does_not_panic() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+default = "hello"
+
+[tasks.hello]
+cmd = "echo hello"
+"#,
+    )
+    .unwrap();
+
+    // `muu select` always tries the picker; inquire fails gracefully without a TTY
+    muu()
+        .arg("select")
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("hello").not());
+}
+
+#[test]
+fn bare_invocation_runs_default_task() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("muu.toml"),
+        r#"
+default = "dev"
+
+[tasks.dev]
+cmd = "echo dev-server"
+"#,
+    )
+    .unwrap();
+
+    muu()
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dev-server"));
+}
+
+// XDG_STATE_HOME only moves the history on Linux
+#[cfg(target_os = "linux")]
+#[test]
+fn history_clear_removes_saved_values() {
+    let dir = TempDir::new().unwrap();
+    let state = TempDir::new().unwrap();
+    let history = state.path().join("muu").join("history");
+    std::fs::create_dir_all(&history).unwrap();
+    std::fs::write(history.join("0123456789abcdef.json"), "{}").unwrap();
+
+    muu()
+        .args(["history", "clear"])
+        .env("XDG_STATE_HOME", state.path())
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Cleared the argument history of this project\n");
+    assert!(history.join("0123456789abcdef.json").exists());
+
+    muu()
+        .args(["history", "clear", "--all"])
+        .env("XDG_STATE_HOME", state.path())
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Cleared the argument history of all projects\n");
+    assert!(!history.exists());
 }
@@ -0,0 +1,130 @@
+#!/bin/sh
+# deploy: Deploy the site
+# Generated by `muu export-script deploy`; edit muu.toml instead.
+#
+# Usage: deploy [dir] [bucket] [tag]
+#    or: deploy [--dir=VALUE] [--bucket=VALUE] [--tag=VALUE] [--dryrun]
+set -e
+
+die() {
+  printf '\033[31mError: %s\033[0m\n' "$1" >&2
+  exit "${2:-1}"
+}
+
+# Replace every occurrence of $1 in $expanded with $2.
+replace() {
+  rest=$expanded
+  expanded=
+  while :; do
+    case $rest in
+      *"$1"*)
+        expanded=$expanded${rest%%"$1"*}$2
+        rest=${rest#*"$1"}
+        ;;
+      *) break ;;
+    esac
+  done
+  expanded=$expanded$rest
+}
+
+expand() {
+  expanded=$1
+  replace '$bucket' "$arg_2"
+  replace '$dryrun' "$arg_4"
+  replace '$dir' "$arg_1"
+  replace '$tag' "$arg_3"
+}
+
+passthrough=
+has_passthrough=
+arg_1=
+given_1=
+arg_2=
+given_2=
+arg_3=
+given_3=
+arg_4=
+given_4=
+
+quote() {
+  case $1 in
+    ''|*[!A-Za-z0-9_./:=@%+,-]*) printf "'%s'" "$(printf '%s' "$1" | sed "s/'/'\\\\''/g")" ;;
+    *) printf '%s' "$1" ;;
+  esac
+}
+
+is_number() {
+  printf '%s\n' "$1" | grep -Eq '^[+-]?([0-9]+\.?[0-9]*|\.[0-9]+)([eE][+-]?[0-9]+)?$'
+}
+
+named() {
+  case $1 in
+    dir) [ -z "$given_1" ] || die "argument 'dir' given more than once"; arg_1=$2; given_1=1 ;;
+    bucket) [ -z "$given_2" ] || die "argument 'bucket' given more than once"; arg_2=$2; given_2=1 ;;
+    tag) [ -z "$given_3" ] || die "argument 'tag' given more than once"; arg_3=$2; given_3=1 ;;
+    dryrun) [ -z "$given_4" ] || die "argument 'dryrun' given more than once"; arg_4=$2; given_4=1 ;;
+    *) die "unknown argument '$1'" ;;
+  esac
+}
+
+positional=
+
+while [ $# -gt 0 ]; do
+  a=$1
+  shift
+  case $a in
+    --) die 'task '\''deploy'\'' does not take arguments after `--`; set `passthrough = true` or use `$@` in its cmd' ;;
+    --*=*) key=${a%%=*} key=${key#--} value=${a#*=} inline=1 ;;
+    --*) key=${a#--} inline=0 ;;
+    -*)
+      if [ "$a" = - ] || is_number "$a"; then
+        positional="$positional $(quote "$a")"
+        continue
+      fi
+      short=${a#-} inline=0
+      case $short in *=*) value=${short#*=} short=${short%%=*} inline=1 ;; esac
+      case $short in
+        *) die "unknown argument '-$short'" ;;
+      esac
+      ;;
+    dir=*|bucket=*|tag=*|dryrun=*) key=${a%%=*} value=${a#*=} inline=1 ;;
+    *) positional="$positional $(quote "$a")"; continue ;;
+  esac
+  if [ "$inline" = 0 ]; then
+    case $key in
+      dryrun) value=true ;;
+      no-dryrun) key=dryrun; value=false ;;
+      dir|bucket|tag)
+        [ $# -gt 0 ] && [ "${1#--}" = "$1" ] || die "argument '$key' needs a value"
+        value=$1
+        shift
+        ;;
+      *) die "unknown argument '$key'" ;;
+    esac
+  fi
+  named "$key" "$value"
+done
+
+eval "set -- $positional"
+if [ -z "$given_1" ] && [ $# -gt 0 ]; then arg_1=$1; given_1=1; shift; fi
+if [ -z "$given_2" ] && [ $# -gt 0 ]; then arg_2=$1; given_2=1; shift; fi
+if [ -z "$given_3" ] && [ $# -gt 0 ]; then arg_3=$1; given_3=1; shift; fi
+[ $# -eq 0 ] || die "too many arguments: expected at most 3, got $((3 + $#))"
+
+[ -n "$given_1" ] || arg_1=./dist
+[ -n "$arg_1" ] || die "missing required argument 'dir'"
+[ -n "$arg_2" ] || die "missing required argument 'bucket'"
+[ -n "$given_4" ] || arg_4=false
+
+case $(printf '%s' "$arg_4" | tr '[:upper:]' '[:lower:]') in
+  true|yes|on|1) arg_4=--dryrun ;;
+  false|no|off|0|'') arg_4='' ;;
+  *) die "invalid value '$arg_4' for argument 'dryrun': expected true or false" ;;
+esac
+
+expand 'echo "deploying $dir to $bucket"
+aws s3 sync $dir s3://$bucket --profile $AWS_PROFILE $dryrun
+'
+printf '\033[35m$\033[0m %s\n' "$expanded" >&2
+exec zsh -c "set -e
+$expanded"